@@ -0,0 +1,351 @@
+//! Axis-aligned bounding boxes (rectangles, cuboids, ...) over Points, and an index for finding
+//! which of many boxes overlap.
+
+use std::{
+    collections::HashMap,
+    ops::{Add, Mul, Sub},
+};
+
+use pathfinding::num_traits::{One, ToPrimitive};
+
+use crate::point::Point;
+
+/// An axis-aligned box in D dimensions.  Both corners are inclusive, so a box with min == max
+/// holds exactly one point.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Aabb<const D: usize, T = usize> {
+    pub min: Point<D, T>,
+    pub max: Point<D, T>,
+}
+
+impl<const D: usize, T: Copy + PartialOrd> Aabb<D, T> {
+    /// The box with `a` and `b` as opposite corners, in any order.
+    pub fn new(a: Point<D, T>, b: Point<D, T>) -> Self {
+        Self {
+            min: a.component_min(&b),
+            max: a.component_max(&b),
+        }
+    }
+
+    /// The smallest box containing every point, or None if there are no points.
+    pub fn from_points(points: impl IntoIterator<Item = Point<D, T>>) -> Option<Self> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        Some(points.fold(Self::new(first, first), |bounds, p| Self {
+            min: bounds.min.component_min(&p),
+            max: bounds.max.component_max(&p),
+        }))
+    }
+
+    pub fn contains(&self, p: &Point<D, T>) -> bool {
+        (0..D).all(|i| self.min.coords[i] <= p.coords[i] && p.coords[i] <= self.max.coords[i])
+    }
+
+    /// True if every point of `other` is inside this box.
+    pub fn contains_box(&self, other: &Self) -> bool {
+        self.contains(&other.min) && self.contains(&other.max)
+    }
+
+    /// True if the boxes share at least one point.  Boxes that only touch at an edge intersect,
+    /// since both corners are inclusive.
+    pub fn intersects(&self, other: &Self) -> bool {
+        (0..D).all(|i| {
+            self.min.coords[i] <= other.max.coords[i] && other.min.coords[i] <= self.max.coords[i]
+        })
+    }
+
+    /// The box of points in both boxes, or None if they don't intersect.
+    pub fn intersection(&self, other: &Self) -> Option<Self> {
+        self.intersects(other).then(|| Self {
+            min: self.min.component_max(&other.min),
+            max: self.max.component_min(&other.max),
+        })
+    }
+
+    /// The smallest box containing both boxes.
+    pub fn union(&self, other: &Self) -> Self {
+        Self {
+            min: self.min.component_min(&other.min),
+            max: self.max.component_max(&other.max),
+        }
+    }
+}
+
+impl<const D: usize, T: Copy + Add<Output = T>> Aabb<D, T> {
+    /// Move the box by `delta`.
+    pub fn translate(&self, delta: &Point<D, T>) -> Self {
+        Self {
+            min: self.min + *delta,
+            max: self.max + *delta,
+        }
+    }
+
+    /// Move the box by `delta` along a single axis.
+    pub fn translate_axis(&self, axis: usize, delta: T) -> Self {
+        let mut moved = *self;
+        moved.min.coords[axis] = moved.min.coords[axis] + delta;
+        moved.max.coords[axis] = moved.max.coords[axis] + delta;
+        moved
+    }
+}
+
+impl<const D: usize, T: Copy + Add<Output = T> + Sub<Output = T>> Aabb<D, T> {
+    /// Move the box along one axis so that its min on that axis is `min`, keeping its size.
+    /// Handy for unsigned coordinates, where moving backwards can't be done with translate.
+    pub fn with_axis_min(&self, axis: usize, min: T) -> Self {
+        let mut moved = *self;
+        moved.max.coords[axis] = min + (self.max.coords[axis] - self.min.coords[axis]);
+        moved.min.coords[axis] = min;
+        moved
+    }
+}
+
+impl<const D: usize, T: Copy + One + Add<Output = T> + Sub<Output = T>> Aabb<D, T> {
+    /// The number of points along each axis.
+    pub fn size(&self) -> Point<D, T> {
+        self.max - self.min + [T::one(); D].into()
+    }
+
+    /// The number of points in the box (its area in 2D).
+    pub fn volume(&self) -> T
+    where
+        T: Mul<Output = T>,
+    {
+        self.size().coords.into_iter().fold(T::one(), |v, s| v * s)
+    }
+}
+
+impl<const D: usize, T: Copy + PartialOrd + One + Add<Output = T>> Aabb<D, T> {
+    /// Every point in the box, with the first axis changing fastest (reading order in 2D).
+    pub fn points(&self) -> Points<D, T> {
+        Points {
+            bounds: *self,
+            next: Some(self.min),
+        }
+    }
+}
+
+/// An iterator over the points in an Aabb.  Produced by Aabb::points.
+pub struct Points<const D: usize, T> {
+    bounds: Aabb<D, T>,
+    next: Option<Point<D, T>>,
+}
+
+impl<const D: usize, T: Copy + PartialOrd + One + Add<Output = T>> Iterator for Points<D, T> {
+    type Item = Point<D, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.next?;
+        let mut next = current;
+        self.next = None;
+        // count like an odometer, carrying into the next axis when one passes its max
+        for axis in 0..D {
+            if next.coords[axis] < self.bounds.max.coords[axis] {
+                next.coords[axis] = next.coords[axis] + T::one();
+                self.next = Some(next);
+                break;
+            }
+            next.coords[axis] = self.bounds.min.coords[axis];
+        }
+        Some(current)
+    }
+}
+
+/// Finds which boxes overlap a query box without testing every box.  Space is split into cubes
+/// of `cell_size` along each axis, and each box is listed in every cube it touches, so a query
+/// only needs to test the boxes sharing a cube with it.  Works best when `cell_size` is around
+/// the size of a typical box.
+#[derive(Debug, Clone)]
+pub struct AabbIndex<const D: usize, T = usize> {
+    cell_size: i64,
+    /// Every box ever inserted, by id.  None once removed.
+    boxes: Vec<Option<Aabb<D, T>>>,
+    buckets: HashMap<[i64; D], Vec<usize>>,
+}
+
+impl<const D: usize, T: Copy + PartialOrd + ToPrimitive> AabbIndex<D, T> {
+    pub fn new(cell_size: usize) -> Self {
+        assert!(cell_size > 0, "cell_size must be positive");
+        Self {
+            cell_size: cell_size as i64,
+            boxes: vec![],
+            buckets: HashMap::new(),
+        }
+    }
+
+    /// The cubes a box touches.
+    fn cells(&self, b: &Aabb<D, T>) -> Vec<[i64; D]> {
+        let cell = |p: &Point<D, T>| -> Point<D, i64> {
+            p.coords
+                .map(|c| c.to_i64().unwrap().div_euclid(self.cell_size))
+                .into()
+        };
+        Aabb::new(cell(&b.min), cell(&b.max))
+            .points()
+            .map(|p| p.coords)
+            .collect()
+    }
+
+    /// Add a box, returning its id.
+    pub fn insert(&mut self, b: Aabb<D, T>) -> usize {
+        let id = self.boxes.len();
+        for cell in self.cells(&b) {
+            self.buckets.entry(cell).or_default().push(id);
+        }
+        self.boxes.push(Some(b));
+        id
+    }
+
+    /// Remove a box, returning it if it was present.
+    pub fn remove(&mut self, id: usize) -> Option<Aabb<D, T>> {
+        let b = self.boxes.get_mut(id)?.take()?;
+        for cell in self.cells(&b) {
+            if let Some(ids) = self.buckets.get_mut(&cell) {
+                ids.retain(|&other| other != id);
+                if ids.is_empty() {
+                    self.buckets.remove(&cell);
+                }
+            }
+        }
+        Some(b)
+    }
+
+    /// Replace the box with the given id, keeping the id.  A removed box's id can be updated to
+    /// put a box back under it.  Returns false, leaving the index alone, if `id` was never
+    /// handed out by insert.
+    pub fn update(&mut self, id: usize, b: Aabb<D, T>) -> bool {
+        if id >= self.boxes.len() {
+            return false;
+        }
+        self.remove(id);
+        for cell in self.cells(&b) {
+            self.buckets.entry(cell).or_default().push(id);
+        }
+        self.boxes[id] = Some(b);
+        true
+    }
+
+    pub fn get(&self, id: usize) -> Option<&Aabb<D, T>> {
+        self.boxes.get(id)?.as_ref()
+    }
+
+    /// The ids of every box intersecting `query`, in ascending order.
+    pub fn query(&self, query: &Aabb<D, T>) -> Vec<usize> {
+        let mut ids: Vec<usize> = self
+            .cells(query)
+            .iter()
+            .filter_map(|cell| self.buckets.get(cell))
+            .flatten()
+            .copied()
+            .filter(|&id| self.boxes[id].is_some_and(|b| b.intersects(query)))
+            .collect();
+        ids.sort_unstable();
+        ids.dedup();
+        ids
+    }
+
+    /// Every pair of intersecting boxes, as (lower id, higher id), in ascending order.
+    pub fn overlapping_pairs(&self) -> Vec<(usize, usize)> {
+        let mut pairs: Vec<(usize, usize)> = self
+            .buckets
+            .values()
+            .flat_map(|ids| {
+                ids.iter().enumerate().flat_map(move |(i, &a)| {
+                    ids[i + 1..].iter().map(move |&b| (a.min(b), a.max(b)))
+                })
+            })
+            .filter(|&(a, b)| {
+                let (a, b) = (self.boxes[a].unwrap(), self.boxes[b].unwrap());
+                a.intersects(&b)
+            })
+            .collect();
+        pairs.sort_unstable();
+        pairs.dedup();
+        pairs
+    }
+
+    /// The number of boxes in the index.
+    pub fn len(&self) -> usize {
+        self.boxes.iter().flatten().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod aabb_tests {
+    use super::*;
+
+    fn cuboid(min: [usize; 3], max: [usize; 3]) -> Aabb<3> {
+        Aabb::new(min.into(), max.into())
+    }
+
+    #[test]
+    fn aabb_test() {
+        let a = cuboid([0, 0, 0], [2, 2, 2]);
+        let b = cuboid([2, 1, 0], [4, 1, 0]);
+        assert!(a.contains(&[1, 2, 0].into()));
+        assert!(!a.contains(&[3, 0, 0].into()));
+        assert!(a.intersects(&b));
+        assert_eq!(a.intersection(&b), Some(cuboid([2, 1, 0], [2, 1, 0])));
+        assert_eq!(a.union(&b), cuboid([0, 0, 0], [4, 2, 2]));
+        assert_eq!(a.volume(), 27);
+        assert_eq!(b.size(), [3, 1, 1].into());
+        assert!(!b.intersects(&b.translate_axis(2, 1)));
+        assert_eq!(b.with_axis_min(0, 0), cuboid([0, 1, 0], [2, 1, 0]));
+    }
+
+    #[test]
+    fn from_points_test() {
+        let points: Vec<Point<2, i64>> = vec![[0, 0].into(), [3, -2].into(), [-1, 4].into()];
+        let bounds = Aabb::from_points(points).unwrap();
+        assert_eq!(bounds, Aabb::new([-1, -2].into(), [3, 4].into()));
+        assert_eq!(
+            bounds.translate(&[1, 2].into()),
+            Aabb::new([0, 0].into(), [4, 6].into())
+        );
+        assert_eq!(Aabb::<2, i64>::from_points(vec![]), None);
+    }
+
+    #[test]
+    fn points_test() {
+        let b: Aabb<2> = Aabb::new([1, 5].into(), [2, 6].into());
+        let points: Vec<_> = b.points().map(|p| p.coords).collect();
+        assert_eq!(points, vec![[1, 5], [2, 5], [1, 6], [2, 6]]);
+        assert_eq!(b.points().count(), b.volume());
+    }
+
+    #[test]
+    fn index_test() {
+        let mut index = AabbIndex::new(2);
+        let a = index.insert(cuboid([0, 0, 1], [2, 0, 1]));
+        let b = index.insert(cuboid([1, 0, 2], [1, 2, 2]));
+        let c = index.insert(cuboid([5, 5, 5], [5, 5, 9]));
+        assert_eq!(index.query(&cuboid([0, 0, 0], [9, 9, 1])), vec![a]);
+        assert_eq!(index.query(&cuboid([1, 0, 0], [1, 0, 9])), vec![a, b]);
+        assert_eq!(index.overlapping_pairs(), vec![]);
+
+        assert!(index.update(b, cuboid([1, 0, 1], [1, 2, 1])));
+        assert_eq!(index.overlapping_pairs(), vec![(a, b)]);
+        assert_eq!(index.remove(c), Some(cuboid([5, 5, 5], [5, 5, 9])));
+        assert_eq!(index.query(&cuboid([5, 5, 5], [5, 5, 5])), vec![]);
+        assert_eq!(index.len(), 2);
+    }
+
+    #[test]
+    fn index_update_unknown_test() {
+        let mut index = AabbIndex::new(2);
+        let a = index.insert(cuboid([0, 0, 0], [1, 1, 1]));
+        assert!(!index.update(a + 1, cuboid([0, 0, 0], [1, 1, 1])));
+        assert_eq!(index.len(), 1);
+        assert_eq!(index.overlapping_pairs(), vec![]);
+        assert_eq!(index.query(&cuboid([0, 0, 0], [9, 9, 9])), vec![a]);
+
+        // a removed id can be given a box again
+        index.remove(a);
+        assert!(index.update(a, cuboid([5, 5, 5], [5, 5, 5])));
+        assert_eq!(index.query(&cuboid([0, 0, 0], [9, 9, 9])), vec![a]);
+    }
+}
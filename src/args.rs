@@ -8,6 +8,16 @@ Options:
   -p, --part        specifies the part
   -e, --example     use the day's example input from examples/
   -i, --input       specify an alternate input file
+  --record <path>   record the visualization to a .cast, .gif, or directory of PNGs
+                    (requires the visualize feature)
+  --record-every <n>
+                    record only every nth frame
+  --max-duration <seconds>
+                    stop recording once the recording is this long
+  --step            pause at every debugger checkpoint
+  --break-at <label>:<n>
+                    pause the nth time the checkpoint <label> is reached (repeatable)
+  --dot <path>      write the day's graph in Graphviz DOT format (days 8 and 19)
   -h, --help        display usage information
 ";
 
@@ -25,6 +35,18 @@ pub struct Args {
     pub example: bool,
     /// specify an alternate input file
     pub input: Option<String>,
+    /// record the visualization to a file or directory
+    pub record: Option<String>,
+    /// record only every nth frame
+    pub record_every: usize,
+    /// stop recording once the recording is this many seconds long
+    pub max_duration: Option<f64>,
+    /// pause at every debugger checkpoint
+    pub step: bool,
+    /// checkpoints to pause at, as label:n
+    pub break_at: Vec<String>,
+    /// write the day's graph in DOT format to this path
+    pub dot: Option<String>,
 }
 
 pub fn parse_args() -> Result<Args, pico_args::Error> {
@@ -40,6 +62,12 @@ pub fn parse_args() -> Result<Args, pico_args::Error> {
         part: pargs.value_from_str(["-p", "--part"]).or(Ok(1))?,
         example: pargs.contains(["-e", "--example"]),
         input: pargs.opt_value_from_str(["-i", "--input"])?,
+        record: pargs.opt_value_from_str("--record")?,
+        record_every: pargs.opt_value_from_str("--record-every")?.unwrap_or(1),
+        max_duration: pargs.opt_value_from_str("--max-duration")?,
+        step: pargs.contains("--step"),
+        break_at: pargs.values_from_str("--break-at")?,
+        dot: pargs.opt_value_from_str("--dot")?,
     };
 
     if pargs.contains(["-e", "--example"]) && pargs.contains(["-i", "--input"]) {
@@ -0,0 +1,284 @@
+use std::{
+    fmt::Display,
+    ops::{BitAnd, BitOr, BitXor, Not},
+};
+
+use crate::{direction::CardDir, grid::Grid};
+
+/// A grid of booleans packed into u64 words, for puzzles that boil down to set membership.  Each
+/// row starts on a fresh word, and bit `x % 64` of word `x / 64` holds column x.  Unused bits at
+/// the end of a row are always zero.
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct BitGrid {
+    width: usize,
+    height: usize,
+    words_per_row: usize,
+    words: Vec<u64>,
+}
+
+impl BitGrid {
+    /// Create a grid with every cell unset.
+    pub fn new(width: usize, height: usize) -> Self {
+        let words_per_row = width.div_ceil(64);
+        Self {
+            width,
+            height,
+            words_per_row,
+            words: vec![0; words_per_row * height],
+        }
+    }
+
+    /// Build a bit grid from any grid, setting the cells for which `is_set` returns true.
+    pub fn from_grid<T: Copy>(grid: &Grid<T>, is_set: impl Fn(T) -> bool) -> Self {
+        let mut bits = Self::new(grid.width(), grid.height());
+        for (y, row) in grid.cells.iter().enumerate() {
+            for (x, t) in row.iter().enumerate() {
+                if is_set(*t) {
+                    bits.set(x, y, true);
+                }
+            }
+        }
+        bits
+    }
+
+    /// Convert to a regular grid, using `on` for set cells and `off` for unset ones.
+    pub fn to_grid<T: Copy>(&self, on: T, off: T) -> Grid<T> {
+        Grid::new(
+            (0..self.height)
+                .map(|y| {
+                    (0..self.width)
+                        .map(|x| if self.is_set(x, y) { on } else { off })
+                        .collect()
+                })
+                .collect(),
+        )
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<bool> {
+        (x < self.width && y < self.height).then(|| self.is_set(x, y))
+    }
+
+    fn is_set(&self, x: usize, y: usize) -> bool {
+        self.row(y)[x / 64] & (1 << (x % 64)) != 0
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, value: bool) {
+        assert!(
+            x < self.width && y < self.height,
+            "({x}, {y}) is out of bounds"
+        );
+        let word = &mut self.row_mut(y)[x / 64];
+        if value {
+            *word |= 1 << (x % 64);
+        } else {
+            *word &= !(1 << (x % 64));
+        }
+    }
+
+    /// The packed words of a single row.
+    pub fn row(&self, y: usize) -> &[u64] {
+        &self.words[y * self.words_per_row..(y + 1) * self.words_per_row]
+    }
+
+    fn row_mut(&mut self, y: usize) -> &mut [u64] {
+        &mut self.words[y * self.words_per_row..(y + 1) * self.words_per_row]
+    }
+
+    /// Count the set cells in the whole grid.
+    pub fn count_ones(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// Count the set cells in a single row.
+    pub fn row_count_ones(&self, y: usize) -> usize {
+        self.row(y).iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// Move every set cell one step in the given direction.  Cells shifted off the edge are lost,
+    /// and the cells shifted in are unset.
+    pub fn shift(&self, dir: CardDir) -> BitGrid {
+        let mut shifted = BitGrid::new(self.width, self.height);
+        let n = self.words_per_row;
+        match dir {
+            CardDir::Up => {
+                shifted.words[..n * self.height.saturating_sub(1)]
+                    .copy_from_slice(&self.words[n.min(self.words.len())..]);
+            }
+            CardDir::Down => {
+                shifted.words[n.min(self.words.len())..]
+                    .copy_from_slice(&self.words[..n * self.height.saturating_sub(1)]);
+            }
+            CardDir::Left => {
+                for y in 0..self.height {
+                    let row = self.row(y);
+                    let new_row = shifted.row_mut(y);
+                    for i in 0..n {
+                        let carry = row.get(i + 1).map(|w| w << 63).unwrap_or(0);
+                        new_row[i] = (row[i] >> 1) | carry;
+                    }
+                }
+            }
+            CardDir::Right => {
+                for y in 0..self.height {
+                    let row = self.row(y);
+                    let new_row = shifted.row_mut(y);
+                    for i in 0..n {
+                        let carry = if i > 0 { row[i - 1] >> 63 } else { 0 };
+                        new_row[i] = (row[i] << 1) | carry;
+                    }
+                }
+                shifted.clear_padding();
+            }
+        }
+        shifted
+    }
+
+    /// Zero the unused bits past the end of each row.
+    fn clear_padding(&mut self) {
+        let used = self.width % 64;
+        if used != 0 {
+            let mask = (1 << used) - 1;
+            for y in 0..self.height {
+                if let Some(last) = self.row_mut(y).last_mut() {
+                    *last &= mask;
+                }
+            }
+        }
+    }
+
+    /// Combine two grids of the same size word by word.
+    fn zip_with(&self, rhs: &BitGrid, op: impl Fn(u64, u64) -> u64) -> BitGrid {
+        assert_eq!(
+            (self.width, self.height),
+            (rhs.width, rhs.height),
+            "bit grids must be the same size"
+        );
+        BitGrid {
+            words: self
+                .words
+                .iter()
+                .zip(&rhs.words)
+                .map(|(a, b)| op(*a, *b))
+                .collect(),
+            ..*self
+        }
+    }
+}
+
+impl BitAnd for &BitGrid {
+    type Output = BitGrid;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        self.zip_with(rhs, |a, b| a & b)
+    }
+}
+
+impl BitOr for &BitGrid {
+    type Output = BitGrid;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        self.zip_with(rhs, |a, b| a | b)
+    }
+}
+
+impl BitXor for &BitGrid {
+    type Output = BitGrid;
+
+    fn bitxor(self, rhs: Self) -> Self::Output {
+        self.zip_with(rhs, |a, b| a ^ b)
+    }
+}
+
+impl Not for &BitGrid {
+    type Output = BitGrid;
+
+    fn not(self) -> Self::Output {
+        let mut inverted = BitGrid {
+            words: self.words.iter().map(|w| !w).collect(),
+            ..*self
+        };
+        inverted.clear_padding();
+        inverted
+    }
+}
+
+impl From<&Grid<bool>> for BitGrid {
+    fn from(grid: &Grid<bool>) -> Self {
+        BitGrid::from_grid(grid, |b| b)
+    }
+}
+
+impl From<&BitGrid> for Grid<bool> {
+    fn from(bits: &BitGrid) -> Self {
+        bits.to_grid(true, false)
+    }
+}
+
+impl Display for BitGrid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for y in 0..self.height {
+            for x in 0..self.width {
+                write!(f, "{}", if self.is_set(x, y) { '#' } else { '.' })?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod bit_grid_tests {
+    use super::*;
+
+    fn from_str(s: &str) -> BitGrid {
+        BitGrid::from_grid(&Grid::from_rows(s.lines()), |c| c == '#')
+    }
+
+    #[test]
+    fn bit_grid_round_trip_test() {
+        let g = Grid::new(vec![vec![true, false, true], vec![false, true, false]]);
+        let bits = BitGrid::from(&g);
+        assert_eq!(bits.count_ones(), 3);
+        assert_eq!(bits.row_count_ones(1), 1);
+        assert_eq!(Grid::<bool>::from(&bits), g);
+    }
+
+    #[test]
+    fn bit_grid_ops_test() {
+        let a = from_str("##..\n#...");
+        let b = from_str("#.#.\n#..#");
+        assert_eq!((&a & &b).to_string(), "#...\n#...\n");
+        assert_eq!((&a | &b).to_string(), "###.\n#..#\n");
+        assert_eq!((&a ^ &b).to_string(), ".##.\n...#\n");
+        assert_eq!((!&a).to_string(), "..##\n.###\n");
+    }
+
+    #[test]
+    fn bit_grid_shift_test() {
+        let g = from_str("#..#\n.##.");
+        assert_eq!(g.shift(CardDir::Right).to_string(), ".#..\n..##\n");
+        assert_eq!(g.shift(CardDir::Left).to_string(), "..#.\n##..\n");
+        assert_eq!(g.shift(CardDir::Up).to_string(), ".##.\n....\n");
+        assert_eq!(g.shift(CardDir::Down).to_string(), "....\n#..#\n");
+    }
+
+    #[test]
+    fn bit_grid_shift_across_words_test() {
+        let mut g = BitGrid::new(130, 1);
+        g.set(63, 0, true);
+        g.set(129, 0, true);
+        let right = g.shift(CardDir::Right);
+        assert_eq!(right.get(64, 0), Some(true));
+        assert_eq!(right.count_ones(), 1);
+        let left = right.shift(CardDir::Left).shift(CardDir::Left);
+        assert_eq!(left.get(62, 0), Some(true));
+    }
+}
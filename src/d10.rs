@@ -6,6 +6,8 @@ use std::fmt::Display;
 use crate::{
     grid::{Adj8, Cell, Grid},
     point::Point,
+    render::{Palette, Rgb},
+    visualize::{self, Frame, Visualizer, BLACK, CYAN, DARK_BLUE, DARK_RED, GREEN},
 };
 
 type Model = Layout;
@@ -170,10 +172,48 @@ fn connect(from: Pipe, adj: Adj8<Pipe>) -> Option<([Cell<Pipe>; 2], Pipe)> {
     a.and_then(|a| b.map(|b| ([a, b], from_pipe_type)))
 }
 
+const GREY: Rgb = [64, 64, 64];
+
+/// Draws pipes as box-drawing characters on a black background.
+struct PipePalette;
+
+impl Palette<Pipe> for PipePalette {
+    fn color(&self, _pipe: Pipe) -> Rgb {
+        BLACK
+    }
+
+    fn glyph(&self, pipe: Pipe) -> Option<char> {
+        match pipe {
+            Pipe::NoPipe => None,
+            pipe => Some(Pipe::to_char(&pipe)),
+        }
+    }
+}
+
+/// A frame of the two trails walking out from the start, with their heads marked.
+fn trails_frame(model: &Model, trails: &[Vec<Point<2>>; 2]) -> Frame {
+    let mut frame = Frame::from_grid(&model.grid, &PipePalette);
+    for (trail, color) in trails.iter().zip([DARK_RED, DARK_BLUE]) {
+        for &p in trail {
+            frame.highlight(p, color);
+        }
+    }
+    frame.highlight(model.start, GREY);
+    for trail in trails {
+        let head = *trail.last().unwrap();
+        let ch = Pipe::to_char(&model.grid.cells[head.y()][head.x()]);
+        frame.mark(head, ch, CYAN);
+    }
+    frame.set_status(format!("steps: {}", trails[0].len() - 1));
+    frame
+}
+
 pub fn part1(model: Model) -> Answer {
-    // println!("{}", model.grid);
-    // println!("start: {}", model.start);
+    farthest(model, visualize::live())
+}
 
+/// The number of steps from the start to the farthest point of the loop.
+fn farthest(model: Model, mut viz: impl Visualizer) -> Answer {
     let start_adj = model.grid.adj_8(model.start.x(), model.start.y());
     let start_cell = Cell::new(
         model.start,
@@ -193,6 +233,13 @@ pub fn part1(model: Model) -> Answer {
     // current location for trail 2
     let mut loc2 = start_con[1];
 
+    // every point on each trail, for visualizing
+    let mut trails = [vec![model.start, loc1.pos], vec![model.start, loc2.pos]];
+
+    if viz.is_enabled() {
+        viz.show(&trails_frame(&model, &trails));
+    }
+
     let mut steps = 1;
 
     loop {
@@ -224,6 +271,12 @@ pub fn part1(model: Model) -> Answer {
         last2 = loc2;
         loc2 = con2;
 
+        if viz.is_enabled() {
+            trails[0].push(loc1.pos);
+            trails[1].push(loc2.pos);
+            viz.show(&trails_frame(&model, &trails));
+        }
+
         if loc1 == loc2 {
             break;
         }
@@ -232,10 +285,12 @@ pub fn part1(model: Model) -> Answer {
     steps
 }
 
-pub fn part2(mut model: Model) -> Answer {
-    // println!("{}", model.grid);
-    // println!("start: {}", model.start);
+pub fn part2(model: Model) -> Answer {
+    enclosed(model, visualize::live())
+}
 
+/// The number of tiles enclosed by the loop.
+fn enclosed(mut model: Model, mut viz: impl Visualizer) -> Answer {
     let start_adj = model.grid.adj_8(model.start.x(), model.start.y());
     let start_cell = Cell::new(
         model.start,
@@ -259,10 +314,12 @@ pub fn part2(mut model: Model) -> Answer {
     model.grid.cells[model.start.y()][model.start.x()] = start_type;
     last1.data = start_type;
     last2.data = start_type;
-    // println!("{}", model.grid);
 
-    // the connected pipes in the loop
-    let mut pipes = vec![model.start, loc1.pos, loc2.pos];
+    // the connected pipes in the loop, walking out each way from the start
+    let mut trails = [vec![model.start, loc1.pos], vec![model.start, loc2.pos]];
+    if viz.is_enabled() {
+        viz.show(&trails_frame(&model, &trails));
+    }
 
     loop {
         // continue finding connections to loc1 and loc2 until they are equal
@@ -293,21 +350,22 @@ pub fn part2(mut model: Model) -> Answer {
         last2 = loc2;
         loc2 = con2;
 
+        // both trails reach the meeting cell, so the loop is whole
+        trails[0].push(loc1.pos);
+        trails[1].push(loc2.pos);
         if loc1 == loc2 {
-            pipes.push(loc1.pos);
             break;
-        } else {
-            pipes.push(loc1.pos);
-            pipes.push(loc2.pos);
+        }
+
+        if viz.is_enabled() {
+            viz.show(&trails_frame(&model, &trails));
         }
     }
 
-    // println!("Pipe loop:");
-    // for pipe in &pipes {
-    //     println!("  {pipe:?}");
-    // }
-    //
-    // TODO the pipes array is incomplete!
+    if viz.is_enabled() {
+        viz.show(&trails_frame(&model, &trails));
+    }
+    let pipes = trails.concat();
 
     // it's raycastin' time
 
@@ -351,58 +409,98 @@ pub fn part2(mut model: Model) -> Answer {
         ints % 2 == 1
     };
 
+    // colour each row inside or outside as it's cast, over the finished loop
+    let mut frame = viz.is_enabled().then(|| {
+        let mut frame = Frame::from_grid(&model.grid, &PipePalette);
+        for &p in &pipes {
+            frame.highlight(p, GREY);
+        }
+        frame
+    });
+
     let mut count = 0;
     for y in 0..model.grid.height() {
         for x in 0..model.grid.width() {
             let p = [x, y].into();
             let is_in = within(p);
             if is_in {
-                // println!("{p:?}");
                 count += 1;
             }
+            if let Some(frame) = frame.as_mut() {
+                if is_in {
+                    frame.highlight(p, GREEN);
+                } else if !pipes.contains(&p) {
+                    frame.highlight(p, DARK_BLUE);
+                }
+            }
+        }
+        if let Some(frame) = frame.as_mut() {
+            frame.set_status(format!("inside: {count}"));
+            viz.show(frame);
         }
     }
 
     count
 }
 
-// #[cfg(test)]
-// mod tests {
-//     use super::*;
-//
-//     const INPUT: &str = include_str!("../input/d10");
-//     const EXAMPLE: &str = include_str!("../examples/d10");
-//
-//     // #[test]
-//     // fn d10p1_example_test() {
-//     //     assert_eq!(
-//     //         part1(parse(EXAMPLE.to_string())),
-//     //         "put part 1 example answer here"
-//     //     );
-//     // }
-//
-//     // #[test]
-//     // fn d10p1_input_test() {
-//     //     assert_eq!(
-//     //         part1(parse(INPUT.to_string())),
-//     //         "put part 1 final answer here"
-//     //     );
-//     // }
-//
-//     // #[test]
-//     // fn d10p2_example_test() {
-//     //     assert_eq!(
-//     //         part2(parse(EXAMPLE.to_string())),
-//     //         "put part 2 example answer here"
-//     //     );
-//     // }
-//
-//     // #[test]
-//     // fn d10p2_input_test() {
-//     //     assert_eq!(
-//     //         part2(parse(INPUT.to_string())),
-//     //         "put part 2 final answer here"
-//     //     );
-//     // }
-//
-// }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::visualize::{assert_snapshot, Capture};
+
+    const EXAMPLE: &str = include_str!("../examples/d10");
+    const EXAMPLE_2: &str = include_str!("../examples/d10-2");
+    const EXAMPLE_3: &str = include_str!("../examples/d10-3");
+    const EXAMPLE_4: &str = include_str!("../examples/d10-4");
+
+    #[test]
+    fn d10p1_example_test() {
+        assert_eq!(part1(parse(EXAMPLE.to_string())), 4);
+        assert_eq!(part1(parse(EXAMPLE_2.to_string())), 8);
+    }
+
+    #[test]
+    fn d10p2_example_test() {
+        assert_eq!(part2(parse(EXAMPLE_3.to_string())), 4);
+        assert_eq!(part2(parse(EXAMPLE_4.to_string())), 8);
+    }
+
+    #[test]
+    fn d10p1_visualize_test() {
+        let mut capture = Capture::new();
+        farthest(parse(EXAMPLE.to_string()), &mut capture);
+
+        // the trails start either side of S and meet opposite it
+        let first = capture.frame(0).unwrap();
+        assert_eq!(first.get(1, 1).unwrap().bg, GREY);
+        assert_eq!(first.get(2, 1).unwrap().bg, CYAN);
+        assert_eq!(first.get(1, 2).unwrap().bg, CYAN);
+        let last = capture.last().unwrap();
+        assert_eq!(last.get(3, 3).unwrap().bg, CYAN);
+        assert_eq!(last.status, "steps: 4");
+    }
+
+    #[test]
+    fn d10p2_visualize_test() {
+        let mut capture = Capture::new();
+        enclosed(parse(EXAMPLE_4.to_string()), &mut capture);
+
+        // the finished trails cover the whole loop, and both end on the cell where they meet
+        let walked = capture
+            .frames()
+            .iter()
+            .rfind(|f| f.status.starts_with("steps: "))
+            .unwrap();
+        let loop_len = 2 * part1(parse(EXAMPLE_4.to_string()));
+        let on_loop = walked.cells.iter().filter(|c| c.bg != BLACK).count();
+        assert_eq!(on_loop, loop_len);
+        let heads = walked.cells.iter().filter(|c| c.bg == CYAN).count();
+        assert_eq!(heads, 1);
+
+        let last = capture.last().unwrap();
+        let inside = last.cells.iter().filter(|c| c.bg == GREEN).count();
+        assert_eq!(inside, 8);
+        assert_eq!(last.status, "inside: 8");
+        assert_snapshot("d10_example_4_enclosed", last);
+    }
+}
@@ -3,6 +3,8 @@
 
 use std::cmp::Ordering;
 
+use crate::point::Point;
+
 type Model = (Vec<Vec<u32>>, Vec<usize>, Vec<usize>);
 type Answer = usize;
 
@@ -72,7 +74,7 @@ pub fn part1((mut universe, empty_rows, empty_cols): Model) -> Answer {
     for (y, row) in universe.iter().enumerate() {
         for (x, cell) in row.iter().enumerate() {
             if *cell > 0 {
-                gals.push((x, y));
+                gals.push(Point::<2>::from([x, y]));
             }
         }
     }
@@ -82,7 +84,7 @@ pub fn part1((mut universe, empty_rows, empty_cols): Model) -> Answer {
     for (i, gal_a) in gals.iter().enumerate() {
         for gal_b in gals[(i + 1)..].iter() {
             if gal_a != gal_b {
-                sum += (gal_a.0.abs_diff(gal_b.0)) + (gal_a.1.abs_diff(gal_b.1));
+                sum += gal_a.manhattan(gal_b);
             }
         }
     }
@@ -95,7 +97,7 @@ pub fn part2<const F: usize>((universe, empty_rows, empty_cols): Model) -> Answe
     for (y, row) in universe.iter().enumerate() {
         for (x, cell) in row.iter().enumerate() {
             if *cell > 0 {
-                gals.push((x, y));
+                gals.push(Point::<2>::from([x, y]));
             }
         }
     }
@@ -105,7 +107,7 @@ pub fn part2<const F: usize>((universe, empty_rows, empty_cols): Model) -> Answe
     for (i, gal_a) in gals.iter().enumerate() {
         for gal_b in gals[(i + 1)..].iter() {
             if gal_a != gal_b {
-                let y_empty_count = if gal_a.1 == gal_b.1 {
+                let y_empty_count = if gal_a.y() == gal_b.y() {
                     // if y values are equal there's no room to add additional expansion
                     0
                 } else {
@@ -113,14 +115,14 @@ pub fn part2<const F: usize>((universe, empty_rows, empty_cols): Model) -> Answe
                     empty_rows
                         .iter()
                         .filter(|&&y| {
-                            // true if empty_col y value is between gal_a.1 and gal_b.1
-                            match gal_a.1.cmp(&gal_b.1) {
+                            // true if empty_col y value is between gal_a.y() and gal_b.y()
+                            match gal_a.y().cmp(&gal_b.y()) {
                                 Ordering::Less => {
-                                    let between = gal_a.1 < y && y < gal_b.1;
+                                    let between = gal_a.y() < y && y < gal_b.y();
                                     between
                                 }
                                 Ordering::Greater => {
-                                    let between = gal_b.1 < y && y < gal_a.1;
+                                    let between = gal_b.y() < y && y < gal_a.y();
                                     between
                                 }
                                 Ordering::Equal => false,
@@ -129,7 +131,7 @@ pub fn part2<const F: usize>((universe, empty_rows, empty_cols): Model) -> Answe
                         .count()
                 };
 
-                let x_empty_count = if gal_a.0 == gal_b.0 {
+                let x_empty_count = if gal_a.x() == gal_b.x() {
                     // if x values are equal there's no room to add additional expansion
                     0
                 } else {
@@ -137,15 +139,15 @@ pub fn part2<const F: usize>((universe, empty_rows, empty_cols): Model) -> Answe
                     empty_cols
                         .iter()
                         .filter(|&&y| {
-                            // true if empty_col y value is between gal_a.0 and gal_b.0
-                            match gal_a.0.cmp(&gal_b.0) {
+                            // true if empty_col y value is between gal_a.x() and gal_b.x()
+                            match gal_a.x().cmp(&gal_b.x()) {
                                 Ordering::Less => {
-                                    let between = gal_a.0 < y && y < gal_b.0;
+                                    let between = gal_a.x() < y && y < gal_b.x();
                                     between
                                 }
 
                                 Ordering::Greater => {
-                                    let between = gal_b.0 < y && y < gal_a.0;
+                                    let between = gal_b.x() < y && y < gal_a.x();
                                     between
                                 }
                                 Ordering::Equal => false,
@@ -154,9 +156,7 @@ pub fn part2<const F: usize>((universe, empty_rows, empty_cols): Model) -> Answe
                         .count()
                 };
 
-                let dist = (gal_a.0.abs_diff(gal_b.0)) + (gal_a.1.abs_diff(gal_b.1))
-                    - y_empty_count
-                    - x_empty_count
+                let dist = gal_a.manhattan(gal_b) - y_empty_count - x_empty_count
                     + y_empty_count * F
                     + x_empty_count * F;
                 sum += dist;
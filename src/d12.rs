@@ -3,6 +3,8 @@
 
 use cached::proc_macro::cached;
 
+use crate::debugger;
+
 type Model = Vec<(String, Vec<u8>)>;
 type Answer = u128;
 
@@ -189,9 +191,7 @@ fn solve(
         step_same = true;
     }
 
-    // wait for user to press enter
-    // std::io::stdin().read_line(&mut String::new()).unwrap();
-    // std::thread::sleep_ms(500);
+    debugger::checkpoint("solve", &(&condition, base, pattern, accept_pat, win));
 
     let win_score = if win { 1 } else { 0 };
 
@@ -3,7 +3,13 @@
 
 use std::fmt::Display;
 
-use crate::{direction::CardDir, grid::Grid};
+use crate::{
+    direction::CardDir,
+    grid::Grid,
+    interner::{Cycle, Interner},
+    render::{Palette, Rgb},
+    visualize::{self, sparkline, Frame, Visualizer, BLACK, GREEN},
+};
 
 type Model = Platform;
 type Answer = usize;
@@ -57,6 +63,43 @@ impl Platform {
 
         score
     }
+
+    /// A frame of the platform, with a sparkline of the most recent `loads` that fit below it.
+    /// Once a cycle is found, the loads in the repeating part are highlighted.
+    fn frame(&self, loads: &[usize], cycle: Option<Cycle>) -> Frame {
+        let mut frame = Frame::from_grid(&self.grid, &RockPalette);
+        let first_shown = loads.len().saturating_sub(frame.width);
+        frame.push_row(&sparkline(&loads[first_shown..]), BLACK);
+        if let Some(cycle) = cycle {
+            let y = frame.height - 1;
+            for i in cycle.start.max(first_shown)..loads.len() {
+                frame.highlight([i - first_shown, y].into(), GREEN);
+            }
+        }
+        frame
+    }
+}
+
+const GREY: Rgb = [96, 96, 96];
+
+/// Draws round rocks as `O` and cube rocks as `#` on grey.
+struct RockPalette;
+
+impl Palette<Rock> for RockPalette {
+    fn color(&self, rock: Rock) -> Rgb {
+        match rock {
+            Rock::Cube => GREY,
+            Rock::Round | Rock::Empty => BLACK,
+        }
+    }
+
+    fn glyph(&self, rock: Rock) -> Option<char> {
+        match rock {
+            Rock::Round => Some('O'),
+            Rock::Cube => Some('#'),
+            Rock::Empty => None,
+        }
+    }
 }
 
 fn roll_vec(v: &[Rock], dir: CardDir) -> Vec<Rock> {
@@ -72,7 +115,7 @@ fn roll_vec(v: &[Rock], dir: CardDir) -> Vec<Rock> {
     v
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Ord, PartialOrd)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Ord, PartialOrd, Hash)]
 pub enum Rock {
     Round,
     Cube,
@@ -120,53 +163,53 @@ pub fn part1(mut model: Model) -> Answer {
     model.score()
 }
 
-pub fn part2(mut model: Model) -> Answer {
-    let mut dirs = [CardDir::Up, CardDir::Left, CardDir::Down, CardDir::Right]
-        .iter()
-        .cycle();
-
-    // seed a cycle detector with guaranteed invalid values
-    let min_cycle_size = 10;
-    let max_cycle_size = 500;
-    let mut seq = vec![0; max_cycle_size];
-    assert!(seq.len() % 2 == 0);
+pub fn part2(model: Model) -> Answer {
+    spin(model, visualize::live())
+}
 
-    let mut cycle = vec![];
-    let mut cycle_at = 0;
+/// The load after a billion spin cycles.
+fn spin(mut model: Model, mut viz: impl Visualizer) -> Answer {
     let total_cycles = 1000000000;
-    // for i in 1..=10 {
-    'outer: for i in 1..=total_cycles {
-        // apply all four directions
-        for _ in 0..4 {
-            model.roll(*dirs.next().unwrap());
-        }
-
-        seq.rotate_left(1);
-        if let Some(last) = seq.last_mut() {
-            *last = model.score();
+    // fingerprints of the platform before each spin cycle, to spot a repeat without keeping every
+    // grid
+    let mut seen = Interner::new();
+    // the load before each spin cycle, which is all that's needed of a state once it's been seen
+    let mut loads = vec![];
+
+    for n in 0..total_cycles {
+        // stop as soon as a whole platform state repeats
+        if let Some(cycle) = seen.find_cycle(model.grid.fingerprint()) {
+            if viz.is_enabled() {
+                let mut frame = model.frame(&loads, Some(cycle));
+                frame.set_status(format!(
+                    "cycle found: start {}, len {}",
+                    cycle.start, cycle.len
+                ));
+                viz.show(&frame);
+            }
+            return loads[cycle.equivalent_step(total_cycles)];
         }
 
-        // split seq into groups of 4 elements
-        seq.reverse();
+        loads.push(model.score());
 
-        for j in min_cycle_size..=(seq.len() / 2) {
-            let chunks: Vec<&[usize]> = seq.chunks(j).collect();
-            if chunks[0] == chunks[1] && i >= max_cycle_size {
-                cycle = Vec::from(chunks[0]);
-                cycle_at = i;
-                break 'outer;
+        // apply all four directions
+        for dir in [CardDir::Up, CardDir::Left, CardDir::Down, CardDir::Right] {
+            model.roll(dir);
+            if viz.is_enabled() {
+                let mut frame = model.frame(&loads, None);
+                frame.set_status(format!("spin {n} tilt {dir:?}: load {}", model.score()));
+                viz.show(&frame);
             }
         }
-
-        seq.reverse();
     }
-    cycle.reverse();
-    cycle[(total_cycles - cycle_at - 1) % cycle.len()]
+
+    model.score()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::visualize::{assert_snapshot, Capture};
 
     const INPUT: &str = include_str!("../input/d14");
     const EXAMPLE: &str = include_str!("../examples/d14");
@@ -186,6 +229,21 @@ mod tests {
         assert_eq!(part2(parse(EXAMPLE.to_string())), 64);
     }
 
+    #[test]
+    fn d14p2_visualize_test() {
+        let mut capture = Capture::new();
+        spin(parse(EXAMPLE.to_string()), &mut capture);
+
+        // four tilts per spin, then one frame when the cycle is found
+        let last = capture.last().unwrap();
+        assert_eq!(last.status, "cycle found: start 3, len 7");
+        assert_eq!(capture.shown(), 10 * 4 + 1);
+        assert_eq!(capture.frame(0).unwrap().status, "spin 0 tilt Up: load 136");
+        let repeating = last.cells.iter().filter(|c| c.bg == GREEN).count();
+        assert_eq!(repeating, 7);
+        assert_snapshot("d14_example_cycle", last);
+    }
+
     #[test]
     fn d14p2_input_test() {
         assert_eq!(part2(parse(INPUT.to_string())), 98029);
@@ -1,8 +1,13 @@
 //! A solution to day 16 year 2023.
 //! https://adventofcode.com/2023/day/16
 
-use crate::{direction::CardDir, grid::Grid, point::Point};
-use console_engine::{pixel, Color, ConsoleEngine, KeyCode};
+use crate::{
+    direction::CardDir,
+    grid::Grid,
+    point::Point,
+    render::{Palette, Rgb},
+    visualize::{self, Frame, Visualizer, BLACK, CYAN, DARK_RED},
+};
 use std::{collections::HashSet, fmt::Display};
 
 type Model = Grid<Tile>;
@@ -17,14 +22,10 @@ pub fn parse(input: String) -> Model {
     )
 }
 
-fn solve(initial_beam: Beam, model: &Model) -> Answer {
+fn solve(initial_beam: Beam, model: &Model, mut viz: impl Visualizer) -> Answer {
     let mut beams = vec![initial_beam];
     let mut energized: HashSet<Beam> = HashSet::new();
 
-    #[cfg(feature = "visualize")]
-    let mut engine = ConsoleEngine::init(model.width() as u32, model.height() as u32, 144)
-        .expect("console visualization couldn't start");
-
     use CardDir::*;
     use Mirror::*;
     use Splitter::*;
@@ -32,73 +33,14 @@ fn solve(initial_beam: Beam, model: &Model) -> Answer {
     while let Some(beam) = beams.pop() {
         energized.insert(beam);
 
-        #[cfg(feature = "visualize")]
-        {
-            if engine.is_key_pressed(KeyCode::Char('q')) || engine.is_key_held(KeyCode::Char('q')) {
-                std::process::exit(1);
+        if viz.is_enabled() {
+            let mut frame = Frame::from_grid(model, &TilePalette);
+            for energized_beam in &energized {
+                frame.highlight(energized_beam.pos, DARK_RED);
             }
-            // print map
-            engine.wait_frame();
-            engine.clear_screen();
-            let energized_points: Vec<_> = energized.iter().map(|beam| beam.pos).collect();
-            for y in 0..model.height() {
-                for x in 0..model.width() {
-                    if beam.pos.x() == x && beam.pos.y() == y {
-                        engine.set_pxl(x as i32, y as i32, pixel::pxl_bg('@', Color::Cyan));
-                    } else if let Some(tile) = model.get(x, y) {
-                        if tile == Tile::Empty && energized_points.contains(&[x, y].into()) {
-                            engine.set_pxl(x as i32, y as i32, pixel::pxl_bg(' ', Color::DarkRed));
-                        } else {
-                            match tile {
-                                Tile::Empty => {
-                                    engine.set_pxl(
-                                        x as i32,
-                                        y as i32,
-                                        pixel::pxl_bg(' ', Color::Black),
-                                    );
-                                }
-                                Tile::Mirror(mirror)
-                                    if energized_points.contains(&[x, y].into()) =>
-                                {
-                                    engine.set_pxl(
-                                        x as i32,
-                                        y as i32,
-                                        pixel::pxl_bg(mirror.into(), Color::DarkRed),
-                                    );
-                                }
-                                Tile::Mirror(mirror) => {
-                                    engine.set_pxl(
-                                        x as i32,
-                                        y as i32,
-                                        pixel::pxl_bg(mirror.into(), Color::Black),
-                                    );
-                                }
-                                Tile::Splitter(split)
-                                    if energized_points.contains(&[x, y].into()) =>
-                                {
-                                    engine.set_pxl(
-                                        x as i32,
-                                        y as i32,
-                                        pixel::pxl_bg(split.into(), Color::DarkRed),
-                                    );
-                                }
-                                Tile::Splitter(split) => {
-                                    engine.set_pxl(
-                                        x as i32,
-                                        y as i32,
-                                        pixel::pxl_bg(split.into(), Color::Black),
-                                    );
-                                }
-                            }
-                        }
-                    } else if energized_points.contains(&[x, y].into()) {
-                        engine.print(x as i32, y as i32, "#");
-                    } else {
-                        engine.set_pxl(x as i32, y as i32, pixel::pxl_bg(' ', Color::Black));
-                    }
-                }
-            }
-            engine.draw();
+            frame.mark(beam.pos, '@', CYAN);
+            frame.set_status(format!("beams: {}", beams.len() + 1));
+            viz.show(&frame);
         }
 
         let new_beams = match (beam.dir, model.get(beam.pos.x(), beam.pos.y())) {
@@ -110,17 +52,9 @@ fn solve(initial_beam: Beam, model: &Model) -> Answer {
                 Some(vec![beam.with_dir(Up), beam.with_dir(Down)])
             }
 
-            // mirrors /
-            (Left, Some(Tile::Mirror(Slash))) => Some(vec![beam.with_dir(Down)]),
-            (Right, Some(Tile::Mirror(Slash))) => Some(vec![beam.with_dir(Up)]),
-            (Up, Some(Tile::Mirror(Slash))) => Some(vec![beam.with_dir(Right)]),
-            (Down, Some(Tile::Mirror(Slash))) => Some(vec![beam.with_dir(Left)]),
-
-            // mirrors \
-            (Left, Some(Tile::Mirror(Slosh))) => Some(vec![beam.with_dir(Up)]),
-            (Right, Some(Tile::Mirror(Slosh))) => Some(vec![beam.with_dir(Down)]),
-            (Up, Some(Tile::Mirror(Slosh))) => Some(vec![beam.with_dir(Left)]),
-            (Down, Some(Tile::Mirror(Slosh))) => Some(vec![beam.with_dir(Right)]),
+            // mirrors
+            (dir, Some(Tile::Mirror(Slash))) => Some(vec![beam.with_dir(dir.reflect_slash())]),
+            (dir, Some(Tile::Mirror(Slosh))) => Some(vec![beam.with_dir(dir.reflect_backslash())]),
 
             // proceed uninterrupted
             (_, Some(Tile::Empty | Tile::Splitter(_))) => Some(vec![beam]),
@@ -156,10 +90,12 @@ pub fn part1(model: Model) -> Answer {
             pos: [0, 0].into(),
         },
         &model,
+        visualize::live(),
     )
 }
 
 pub fn part2(model: Model) -> Answer {
+    let mut viz = visualize::live();
     let mut max = 0;
     for y in 0..model.height() {
         max = max.max(solve(
@@ -168,6 +104,7 @@ pub fn part2(model: Model) -> Answer {
                 pos: [0, y].into(),
             },
             &model,
+            &mut viz,
         ));
         max = max.max(solve(
             Beam {
@@ -175,6 +112,7 @@ pub fn part2(model: Model) -> Answer {
                 pos: [model.width() - 1, y].into(),
             },
             &model,
+            &mut viz,
         ));
     }
     for x in 0..model.width() {
@@ -184,6 +122,7 @@ pub fn part2(model: Model) -> Answer {
                 pos: [x, 0].into(),
             },
             &model,
+            &mut viz,
         ));
         max = max.max(solve(
             Beam {
@@ -191,6 +130,7 @@ pub fn part2(model: Model) -> Answer {
                 pos: [x, model.height() - 1].into(),
             },
             &model,
+            &mut viz,
         ));
     }
     max
@@ -215,6 +155,23 @@ impl Beam {
     }
 }
 
+/// Draws mirrors and splitters as box-drawing characters on a black background.
+struct TilePalette;
+
+impl Palette<Tile> for TilePalette {
+    fn color(&self, _tile: Tile) -> Rgb {
+        BLACK
+    }
+
+    fn glyph(&self, tile: Tile) -> Option<char> {
+        match tile {
+            Tile::Empty => None,
+            Tile::Mirror(mirror) => Some(mirror.into()),
+            Tile::Splitter(split) => Some(split.into()),
+        }
+    }
+}
+
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum Tile {
     Empty,
@@ -328,6 +285,7 @@ impl Display for Splitter {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::visualize::{assert_snapshot, Capture};
 
     const INPUT: &str = include_str!("../input/d16");
     const EXAMPLE: &str = include_str!("../examples/d16");
@@ -337,6 +295,29 @@ mod tests {
         assert_eq!(part1(parse(EXAMPLE.to_string())), 46,);
     }
 
+    #[test]
+    fn d16p1_visualize_test() {
+        let model = parse(EXAMPLE.to_string());
+        let mut capture = Capture::new();
+        let start = Beam {
+            dir: CardDir::Right,
+            pos: [0, 0].into(),
+        };
+        solve(start, &model, &mut capture);
+
+        // one frame per beam step, starting with the beam in the top left corner
+        let first = capture.frame(0).unwrap();
+        assert_eq!(first.get(0, 0).unwrap().ch, '@');
+        assert_eq!(first.get(0, 0).unwrap().bg, CYAN);
+        assert_eq!(first.row_text(1), "┃ ━ ╲     ");
+
+        let last = capture.last().unwrap();
+        let energized = last.cells.iter().filter(|c| c.bg == DARK_RED).count();
+        // the marked beam covers one energized cell
+        assert_eq!(energized + 1, 46);
+        assert_snapshot("d16_example_final", last);
+    }
+
     #[test]
     fn d16p1_input_test() {
         assert_eq!(part1(parse(INPUT.to_string())), 7472);
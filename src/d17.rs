@@ -1,8 +1,15 @@
 //! A solution to day 17 year 2023.
 //! https://adventofcode.com/2023/day/17
 
-use crate::{direction::CardDir, grid::Grid, point::Point};
-use pathfinding::prelude::astar;
+use std::collections::HashSet;
+
+use crate::{
+    direction::CardDir,
+    grid::Grid,
+    pathfinder::{Crucible, Heading},
+    render::{Palette, Rgb},
+    visualize::{self, Frame, Visualizer, CYAN, DARK_BLUE},
+};
 
 type Model = Grid<usize>;
 type Answer = usize;
@@ -20,113 +27,110 @@ pub fn parse(input: String) -> Model {
     )
 }
 
-pub fn part1(model: Model) -> Answer {
-    use CardDir::*;
-    let start: Point<2> = [0, 0].into();
-    let end: Point<2> = [model.width() - 1, model.height() - 1].into();
-
-    // a "fake" starting direction to force all traversals to begin with a "turn"
-    let faux_starting_dir = Up;
-
-    let path = astar(
-        &(start, faux_starting_dir, 3),
-        |(p, dir, straight_rem)| {
-            // tag each adjacent point with its associated direction
-            let [up, left, right, down] = model.adj_4(p.x(), p.y()).cells;
-
-            let up = up.map(|p| (p, Up));
-            let left = left.map(|p| (p, Left));
-            let right = right.map(|p| (p, Right));
-            let down = down.map(|p| (p, Down));
-
-            let successors: Vec<_> = [up, left, right, down]
-                .into_iter()
-                .map(|d| {
-                    if let Some(d) = d {
-                        let new_straight_rem = match (dir, d.1) {
-                            (Up, Up) | (Down, Down) | (Left, Left) | (Right, Right) => {
-                                straight_rem - 1
-                            }
-                            (Up, Down) | (Down, Up) | (Left, Right) | (Right, Left) => 0,
-                            (Up, _) | (Down, _) | (Left, _) | (Right, _) => 3,
-                        };
-                        if new_straight_rem == 0 {
-                            None
-                        } else {
-                            Some((d.0.pos, d.1, new_straight_rem))
-                        }
-                    } else {
-                        None
-                    }
-                })
-                .filter_map(|d| d.map(|d| (d, model.get(d.0.x(), d.0.y()).unwrap())))
-                .collect();
-            successors
-        },
-        |(p, _dir, _straight_rem)| p.x().abs_diff(end.x()) + p.y().abs_diff(end.y()),
-        |(p, _dir, _straight_rem)| *p == end,
-    )
-    .unwrap();
+/// The cheapest route for a crucible from the top left to the bottom right, moving at least
+/// `min_run` and at most `max_run` blocks in a straight line before turning.  Returns every
+/// state along the route, starting with the start, and the total heat loss.
+pub fn best_path(
+    model: &Model,
+    min_run: usize,
+    max_run: usize,
+    mut viz: impl Visualizer,
+) -> (Vec<Heading>, usize) {
+    let rules = Crucible {
+        min_run,
+        max_run,
+        allow_reverse: false,
+        end: [model.width() - 1, model.height() - 1].into(),
+    };
+
+    // blocks the search has expanded a state at, for visualizing
+    let mut explored = HashSet::new();
+    let mut expansions = 0;
+    let path = model
+        .find_path_watched(&rules, Heading::start([0, 0].into()), |state| {
+            if !viz.is_enabled() {
+                return;
+            }
+            explored.insert(state.pos);
+            expansions += 1;
+            // one frame per grid width of expansions keeps big inputs watchable
+            if expansions % model.width() == 0 {
+                let mut frame = heat_frame(model);
+                for &p in &explored {
+                    frame.highlight(p, EXPLORED);
+                }
+                frame.mark(state.pos, '@', CYAN);
+                frame.set_status(format!("explored: {}", explored.len()));
+                viz.show(&frame);
+            }
+        })
+        .expect("couldn't find a path");
+
+    if viz.is_enabled() {
+        let mut frame = heat_frame(model);
+        for state in &path.0 {
+            if let Some(dir) = state.dir {
+                frame.mark(state.pos, arrow(dir), run_color(state.run, max_run));
+            }
+        }
+        frame.set_status(format!("heat loss: {}", path.1));
+        viz.show(&frame);
+    }
 
-    path.1
+    path
 }
 
-pub fn part2(model: Model) -> Answer {
-    use CardDir::*;
-
-    let start: Point<2> = [0, 0].into();
-    let end: Point<2> = [model.width() - 1, model.height() - 1].into();
-
-    let path = astar(
-        &(start, None, 0),
-        |(p, dir, straight)| {
-            let successors: Vec<_> = model
-                .adj_4(p.x(), p.y())
-                .cells
-                .into_iter()
-                .zip([Up, Left, Right, Down])
-                .map(|(new_point, new_dir)| {
-                    if let Some(new_point) = new_point {
-                        #[rustfmt::skip]
-                        let new_straight = match (dir, new_dir) {
-                            (None, Down) | (None, Right) | (Some(Up), Up) | (Some(Down), Down) | (Some(Left), Left) | (Some(Right), Right)
-                                if *straight < 10 =>
-                            {
-                                Some(straight + 1)
-                            }
-                            // no continuing if straight >= 10
-                            (Some(Up), Up) | (Some(Down), Down) | (Some(Left), Left) | (Some(Right), Right) => None,
-                            // no 180's
-                            (Some(Up), Down) | (Some(Down), Up) | (Some(Left), Right) | (Some(Right), Left) => None,
-                            // turning allowed if straight >= 4
-                            (Some(Up), _) | (Some(Down), _) | (Some(Left), _) | (Some(Right), _)
-                                if *straight >= 4 =>
-                            {
-                                Some(1)
-                            }
-                            _ => None,
-                        };
-                        new_straight
-                            .map(|new_straight| (new_point.pos, Some(new_dir), new_straight))
-                    } else {
-                        None
-                    }
-                })
-                .filter_map(|d| d.map(|d| (d, model.get(d.0.x(), d.0.y()).unwrap())))
-                .collect();
-            successors
-        },
-        |(p, _dir, _straight_rem)| p.x().abs_diff(end.x()) + p.y().abs_diff(end.y()),
-        |(p, _dir, straight_rem)| *p == end && *straight_rem >= 4,
-    )
-    .expect("couldn't find a path");
+const EXPLORED: Rgb = DARK_BLUE;
+
+/// Draws each block's heat loss as a digit, on brown that's darker for less heat loss.
+struct HeatPalette;
+
+impl Palette<usize> for HeatPalette {
+    fn color(&self, heat: usize) -> Rgb {
+        let level = (heat * 12) as u8;
+        [level, level / 2, 0]
+    }
+
+    fn glyph(&self, heat: usize) -> Option<char> {
+        char::from_digit(heat as u32, 10)
+    }
+}
+
+fn heat_frame(model: &Model) -> Frame {
+    Frame::from_grid(model, &HeatPalette)
+}
 
-    path.1
+/// Yellow at the start of a straight run, shading to red as the run reaches `max_run`.
+fn run_color(run: usize, max_run: usize) -> Rgb {
+    let green = 255 * (max_run - run) / (max_run - 1).max(1);
+    [255, green as u8, 0]
+}
+
+fn arrow(dir: CardDir) -> char {
+    match dir {
+        CardDir::Up => '^',
+        CardDir::Down => 'v',
+        CardDir::Left => '<',
+        CardDir::Right => '>',
+    }
+}
+
+fn solve(model: Model, min_run: usize, max_run: usize) -> Answer {
+    best_path(&model, min_run, max_run, visualize::live()).1
+}
+
+pub fn part1(model: Model) -> Answer {
+    solve(model, 1, 3)
+}
+
+pub fn part2(model: Model) -> Answer {
+    solve(model, 4, 10)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::visualize::{assert_snapshot, Capture};
 
     const INPUT: &str = include_str!("../input/d17");
     const EXAMPLE: &str = include_str!("../examples/d17");
@@ -137,6 +141,31 @@ mod tests {
         assert_eq!(part1(parse(EXAMPLE.to_string())), 102);
     }
 
+    #[test]
+    fn d17p1_visualize_test() {
+        let model = parse(EXAMPLE.to_string());
+        let mut capture = Capture::new();
+        let (path, heat_loss) = best_path(&model, 1, 3, &mut capture);
+        assert_eq!(heat_loss, 102);
+        assert_eq!(path[0], Heading::start([0, 0].into()));
+        assert_eq!(path.last().unwrap().pos, [12, 12].into());
+
+        // the last frame is the route, one arrow per block after the start
+        let last = capture.last().unwrap();
+        let route = last.cells.iter().filter(|c| "^v<>".contains(c.ch)).count();
+        assert_eq!(route, path.len() - 1);
+        assert_eq!(last.get(1, 0).unwrap().bg, run_color(1, 3));
+        assert_eq!(last.get(2, 0).unwrap().bg, run_color(2, 3));
+        assert!(capture.frame(0).unwrap().status.starts_with("explored: "));
+        assert_snapshot("d17_example_route", last);
+
+        // ultra crucibles take longer runs over the same map
+        let (ultra, heat_loss) = best_path(&model, 4, 10, &mut Capture::new());
+        assert_eq!(heat_loss, 94);
+        let turns = ultra[1..].windows(2).filter(|w| w[0].dir != w[1].dir);
+        assert!(turns.map(|w| w[0].run).all(|run| run >= 4));
+    }
+
     #[test]
     fn d17p1_input_test() {
         assert_eq!(part1(parse(INPUT.to_string())), 859);
@@ -1,7 +1,16 @@
 //! A solution to day 18 year 2023.
 //! https://adventofcode.com/2023/day/18
 
-use crate::direction::CardDir;
+use std::{fmt::Write as _, io, iter::once, path::Path};
+
+use pathfinding::num_traits::Zero;
+
+use crate::{
+    aabb::Aabb,
+    direction::CardDir,
+    point::IPoint,
+    render::{hex, parse_hex, Rgb},
+};
 
 // plan for part 1 and part 2
 type Model = (Plan, Plan);
@@ -22,6 +31,16 @@ pub struct Plan {
 struct Step {
     dir: CardDir,
     mag: i64,
+    /// The colour to paint the trench, for steps read as written (part 1).
+    color: Option<Rgb>,
+}
+
+impl Step {
+    /// How far this step moves the digger.
+    fn delta(&self) -> IPoint<2> {
+        let (dx, dy) = self.dir.delta();
+        IPoint::from([dx, dy]) * self.mag
+    }
 }
 
 impl<S: AsRef<str>> From<S> for Step {
@@ -29,55 +48,31 @@ impl<S: AsRef<str>> From<S> for Step {
         let mut parts = value.as_ref().split_whitespace();
         let dir_text = parts.next().unwrap();
 
-        let dir = match dir_text {
-            "U" => CardDir::Up,
-            "L" => CardDir::Left,
-            "R" => CardDir::Right,
-            "D" => CardDir::Down,
-            _ => unreachable!(),
-        };
+        let dir = dir_text.parse().unwrap();
 
         let mag_text = parts.next().unwrap();
 
         let mag = mag_text.parse().unwrap();
 
-        Step { dir, mag }
+        let color = parts
+            .next()
+            .and_then(|text| parse_hex(text.trim_matches(['(', ')'])));
+
+        Step { dir, mag, color }
     }
 }
 
 pub fn parse(input: String) -> Model {
     fn get_offsets(steps: &[Step]) -> (i64, i64) {
-        let mut sum_x = 0;
-        let mut min_x = 0;
-        let mut max_x = 0;
-
-        let mut sum_y = 0;
-        let mut min_y = 0;
-        let mut max_y = 0;
-
-        for step in steps.iter() {
-            match step.dir {
-                CardDir::Up => {
-                    sum_y -= step.mag;
-                    min_y = min_y.min(sum_y);
-                }
-                CardDir::Down => {
-                    sum_y += step.mag;
-                    max_y = max_y.max(sum_y);
-                }
-                CardDir::Left => {
-                    sum_x -= step.mag;
-                    min_x = min_x.min(sum_x);
-                }
-                CardDir::Right => {
-                    sum_x += step.mag;
-                    max_x = max_x.max(sum_x);
-                }
-            }
-        }
+        let mut pos = IPoint::<2>::zero();
+        let corners = steps.iter().map(|step| {
+            pos = pos + step.delta();
+            pos
+        });
+        let bounds = Aabb::from_points(once(IPoint::zero()).chain(corners)).unwrap();
 
-        let x_offset = -min_x + 1;
-        let y_offset = -min_y + 1;
+        let x_offset = -bounds.min.x() + 1;
+        let y_offset = -bounds.min.y() + 1;
 
         (x_offset, y_offset)
     }
@@ -101,17 +96,13 @@ pub fn parse(input: String) -> Model {
                 let (_, hex) = line.split_at(1 + line.find('#').unwrap());
                 let dist_s = &hex[0..5];
                 let dist = i64::from_str_radix(dist_s, 16).unwrap();
-                let dir_s = &hex[5..6];
-                let dir = match dir_s {
-                    "0" => "R",
-                    "1" => "D",
-                    "2" => "L",
-                    "3" => "U",
-                    _ => unreachable!(),
-                };
-                format!("{} {}", dir, dist)
+                let dir = hex[5..6].parse().unwrap();
+                Step {
+                    dir,
+                    mag: dist,
+                    color: None,
+                }
             })
-            .map(Step::from)
             .collect();
 
         let (x_offset, y_offset) = get_offsets(&steps);
@@ -126,36 +117,150 @@ pub fn parse(input: String) -> Model {
     (p1_plan, p2_plan)
 }
 
-fn solve(plan: Plan) -> Answer {
-    let mut x = plan.x_offset;
-    let mut y = plan.y_offset;
-    let mut lengths = 0;
-
-    let verts: Vec<_> = plan
-        .steps
-        .iter()
-        .map(|step| {
-            lengths += step.mag - 1;
-            match step.dir {
-                CardDir::Up => y -= step.mag,
-                CardDir::Down => y += step.mag,
-                CardDir::Left => x -= step.mag,
-                CardDir::Right => x += step.mag,
-            };
-            //
-            (x, y)
-        })
-        .collect();
+/// The size of a lagoon worked out from its outline alone, to cross-check `solve`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Measure {
+    /// The area inside the path through the middle of the trench, by the shoelace formula.
+    pub area: i64,
+    /// The number of cubes dug for the trench.
+    pub boundary: i64,
+    /// The number of cubes inside the trench, by Pick's theorem.
+    pub interior: i64,
+}
+
+impl Measure {
+    /// The number of cubes in the whole lagoon.
+    pub fn total(&self) -> i64 {
+        self.interior + self.boundary
+    }
+}
+
+const TRENCH: Rgb = [0, 0, 0];
+const LAGOON: Rgb = [128, 192, 255];
+
+impl Plan {
+    /// The corners of the trench, in digging order, ending back at the start.  Shifted by the
+    /// plan's offsets so every coordinate is positive.
+    pub fn vertices(&self) -> Vec<IPoint<2>> {
+        let mut pos: IPoint<2> = [self.x_offset, self.y_offset].into();
+        self.steps
+            .iter()
+            .map(|step| {
+                pos = pos + step.delta();
+                pos
+            })
+            .collect()
+    }
+
+    pub fn measure(&self) -> Measure {
+        let area = shoelace(&self.vertices());
+        let boundary = self.steps.iter().map(|step| step.mag).sum();
+        Measure {
+            area,
+            boundary,
+            // Pick's theorem: area = interior + boundary / 2 - 1, which doesn't hold for a plan
+            // that digs nothing
+            interior: if self.steps.is_empty() {
+                0
+            } else {
+                (area - boundary / 2 + 1).max(0)
+            },
+        }
+    }
+
+    /// Draw the lagoon as an SVG document scaled to fit in `size` user units square.  Each edge
+    /// of the trench is stroked in its step's colour where the plan had one, and the caption
+    /// gives the lagoon's measurements.  A plan that digs nothing draws an empty picture.
+    pub fn to_svg(&self, size: f64) -> String {
+        let mut svg = String::new();
+        writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{size}" height="{size}" viewBox="0 0 {size} {size}">"#,
+        )
+        .unwrap();
+        let verts = self.vertices();
+        let Some(bounds) = Aabb::from_points(verts.iter().copied()) else {
+            svg.push_str("</svg>\n");
+            return svg;
+        };
+        let extent = (bounds.max - bounds.min)
+            .coords
+            .into_iter()
+            .max()
+            .unwrap()
+            .max(1);
+        let font_size = size / 30.0;
+        let stroke = (size / 200.0).max(1.0);
+        let margin = stroke + font_size * 1.5;
+        let scale = (size - 2.0 * margin) / extent as f64;
+        let xy = |p: IPoint<2>| {
+            (
+                (p.x() - bounds.min.x()) as f64 * scale + margin,
+                (p.y() - bounds.min.y()) as f64 * scale + margin,
+            )
+        };
+        let measure = self.measure();
 
+        let points: Vec<String> = verts
+            .iter()
+            .map(|&p| {
+                let (x, y) = xy(p);
+                format!("{x:.2},{y:.2}")
+            })
+            .collect();
+        writeln!(
+            svg,
+            r#"<polygon points="{}" fill="{}" stroke="none"/>"#,
+            points.join(" "),
+            hex(LAGOON),
+        )
+        .unwrap();
+        // each step digs from the previous corner, and the first from the last
+        for (i, step) in self.steps.iter().enumerate() {
+            let (x1, y1) = xy(verts[(i + verts.len() - 1) % verts.len()]);
+            let (x2, y2) = xy(verts[i]);
+            writeln!(
+                svg,
+                r#"<line x1="{x1:.2}" y1="{y1:.2}" x2="{x2:.2}" y2="{y2:.2}" stroke="{}" stroke-width="{stroke:.2}" stroke-linecap="square"/>"#,
+                hex(step.color.unwrap_or(TRENCH)),
+            )
+            .unwrap();
+        }
+        writeln!(
+            svg,
+            r#"<text x="{stroke:.2}" y="{font_size:.2}" font-size="{font_size:.2}" font-family="monospace">area {}, boundary {}, interior {}, total {}</text>"#,
+            measure.area,
+            measure.boundary,
+            measure.interior,
+            measure.total(),
+        )
+        .unwrap();
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// Draw the lagoon to an SVG file.
+    pub fn render_svg(&self, path: impl AsRef<Path>, size: f64) -> io::Result<()> {
+        std::fs::write(path, self.to_svg(size))
+    }
+}
+
+/// The area of a polygon given its corners in order.
+fn shoelace(verts: &[IPoint<2>]) -> i64 {
     let mut a = 0;
     for i in 0..verts.len() {
         let j = (i + 1) % verts.len();
         let s1 = verts[i];
         let s2 = verts[j];
-        a += s1.0 * s2.1;
-        a -= s2.0 * s1.1;
+        a += s1.x() * s2.y();
+        a -= s2.x() * s1.y();
     }
-    a = a.abs() / 2;
+    a.abs() / 2
+}
+
+fn solve(plan: Plan) -> Answer {
+    let lengths: i64 = plan.steps.iter().map(|step| step.mag - 1).sum();
+    let a = shoelace(&plan.vertices());
 
     // find extra area
 
@@ -188,6 +293,54 @@ mod tests {
         assert_eq!(part1(parse(EXAMPLE.to_string())), 62);
     }
 
+    #[test]
+    fn d18p1_measure_test() {
+        let (p1, _) = parse(EXAMPLE.to_string());
+        let verts = p1.vertices();
+        assert_eq!(verts.len(), 14);
+        assert_eq!(verts.last(), Some(&[p1.x_offset, p1.y_offset].into()));
+        let measure = p1.measure();
+        assert_eq!(measure.boundary, 38);
+        assert_eq!(measure.total(), 62);
+    }
+
+    #[test]
+    fn d18p2_measure_test() {
+        let (_, p2) = parse(EXAMPLE.to_string());
+        assert_eq!(p2.measure().total(), 952408144115);
+    }
+
+    #[test]
+    fn d18p1_svg_test() {
+        let (p1, _) = parse(EXAMPLE.to_string());
+        let svg = p1.to_svg(300.0);
+        assert!(svg.starts_with("<svg"));
+        assert_eq!(svg.matches("<line").count(), 14);
+        assert!(svg.contains(r##"stroke="#70c710""##));
+        assert!(svg.contains("total 62</text>"));
+    }
+
+    #[test]
+    fn d18p2_svg_test() {
+        // part 2's huge lagoon is scaled down to the same size
+        let (_, p2) = parse(EXAMPLE.to_string());
+        let svg = p2.to_svg(300.0);
+        assert!(svg.contains(r#"width="300" height="300""#));
+        assert!(svg.contains(r##"stroke="#000000""##));
+        assert!(svg.contains("total 952408144115</text>"));
+    }
+
+    #[test]
+    fn d18p1_empty_plan_test() {
+        let (plan, _) = parse(String::new());
+        assert!(plan.vertices().is_empty());
+        assert_eq!(plan.measure().total(), 0);
+        assert_eq!(
+            plan.to_svg(100.0),
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"100\" height=\"100\" viewBox=\"0 0 100 100\">\n</svg>\n"
+        );
+    }
+
     #[test]
     fn d18p1_input_test() {
         assert_eq!(part1(parse(INPUT.to_string())), 40131);
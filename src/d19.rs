@@ -8,6 +8,11 @@ use std::cmp::{max, min};
 use std::ops::Range;
 use std::{cmp::Ordering, fmt::Display};
 
+use crate::{
+    graph::{Dot, Style},
+    render::Rgb,
+};
+
 type Model<'a> = (IndexMap<String, Workflow>, Vec<Part>);
 type Answer = u64;
 
@@ -268,6 +273,33 @@ pub fn part1((workflows, mut part_queue): Model) -> Answer {
         .sum()
 }
 
+const START_FILL: Rgb = [128, 192, 255];
+const ACCEPT_FILL: Rgb = [128, 255, 128];
+const REJECT_FILL: Rgb = [255, 128, 128];
+
+/// The workflows as a graph, with an edge to each rule's destination labelled with the rule's
+/// condition.  The `in` workflow and the accept and reject ends are filled in.
+pub fn graph(workflows: &IndexMap<String, Workflow>) -> Dot<'_, &str> {
+    Dot::digraph(workflows.iter().map(|(name, workflow)| {
+        let to: Vec<&str> = workflow.reqs.iter().map(|req| req.dst.as_str()).collect();
+        (name.as_str(), to)
+    }))
+    .node_style(|&node| match node {
+        "in" => Style::new().fill(START_FILL),
+        "A" => Style::new().fill(ACCEPT_FILL).shape("box"),
+        "R" => Style::new().fill(REJECT_FILL).shape("box"),
+        _ => Style::new(),
+    })
+    .edge_style(|from, _, i| {
+        let req = &workflows[*from].reqs[i];
+        match req.cmp {
+            Ordering::Less => Style::new().label(format!("{}<{}", req.part_type, req.mag)),
+            Ordering::Greater => Style::new().label(format!("{}>{}", req.part_type, req.mag)),
+            Ordering::Equal => Style::new(),
+        }
+    })
+}
+
 const MAX: u64 = 4000;
 pub fn part2((workflows, _): Model) -> u64 {
     let mut sum: u64 = 0;
@@ -420,6 +452,17 @@ mod tests {
         assert_eq!(part1(parse(EXAMPLE.to_string())), 19114);
     }
 
+    #[test]
+    fn d19p1_graph_test() {
+        let (workflows, _) = parse(EXAMPLE.to_string());
+        let dot = graph(&workflows).to_string();
+        assert!(dot.contains("\"in\" [style=\"filled\", fillcolor=\"#80c0ff\"];"));
+        assert!(dot.contains("\"in\" -> \"px\" [label=\"s<1351\"];"));
+        assert!(dot.contains("\"in\" -> \"qqz\";"));
+        assert!(dot.contains("\"R\" [style=\"filled\", fillcolor=\"#ff8080\", shape=\"box\"];"));
+        assert_eq!(dot.matches(" -> ").count(), 25);
+    }
+
     #[test]
     fn d19p1_input_test() {
         assert_eq!(part1(parse(INPUT.to_string())), 492702);
@@ -3,6 +3,12 @@
 
 use std::collections::HashMap;
 
+use crate::{
+    debugger,
+    graph::{Dot, Style},
+    render::Rgb,
+};
+
 type Model = Map;
 type Answer = usize;
 
@@ -63,7 +69,7 @@ pub fn part2(model: Model) -> Answer {
 
     for (i, mov) in model.dirs.iter().cycle().enumerate() {
         let step = i + 1;
-        // std::thread::sleep(Duration::from_millis(300));
+        debugger::checkpoint("step", &(step, &posi, &cycles));
         // let posi2: Vec<_> = std::mem::take(&mut posi);
         for (i, pos) in posi.iter_mut().enumerate() {
             let dirs = model.nodes.get(*pos).unwrap();
@@ -91,6 +97,30 @@ pub fn part2(model: Model) -> Answer {
     cycles.into_iter().reduce(lcm).unwrap()
 }
 
+const START_FILL: Rgb = [128, 255, 128];
+const END_FILL: Rgb = [255, 128, 128];
+
+/// The network as a graph, with each node's left and right edges labelled.  Start nodes (ending
+/// in A) and end nodes (ending in Z) are filled in.
+pub fn graph(model: &Map) -> Dot<'_, &str> {
+    let mut names: Vec<&String> = model.nodes.keys().collect();
+    names.sort();
+    Dot::digraph(names.into_iter().map(|name| {
+        let (left, right) = &model.nodes[name];
+        (name.as_str(), [left.as_str(), right.as_str()])
+    }))
+    .node_style(|node| {
+        if node.ends_with('A') {
+            Style::new().fill(START_FILL)
+        } else if node.ends_with('Z') {
+            Style::new().fill(END_FILL)
+        } else {
+            Style::new()
+        }
+    })
+    .edge_style(|_, _, i| Style::new().label(["L", "R"][i]))
+}
+
 #[derive(Debug)]
 pub struct Map {
     dirs: Vec<Dir>,
@@ -148,6 +178,16 @@ mod tests {
         assert_eq!(part1(parse(EXAMPLE2.to_string())), 6);
     }
 
+    #[test]
+    fn d8p1_graph_test() {
+        let dot = graph(&parse(EXAMPLE2.to_string())).to_string();
+        assert!(dot.starts_with("digraph {\n    \"AAA\" [style=\"filled\""));
+        assert!(dot.contains("\"ZZZ\" [style=\"filled\", fillcolor=\"#ff8080\"];"));
+        assert!(dot.contains("\"AAA\" -> \"BBB\" [label=\"L\"];"));
+        assert!(dot.contains("\"ZZZ\" -> \"ZZZ\" [label=\"R\"];"));
+        assert_eq!(dot.matches(" -> ").count(), 6);
+    }
+
     #[test]
     fn d8p1_input_test() {
         assert_eq!(part1(parse(INPUT.to_string())), 17263);
@@ -0,0 +1,250 @@
+//! Pause a solution at checkpoints to look at its state.
+//!
+//! Solutions call `checkpoint` wherever stepping through would be useful, with a label and the
+//! state worth looking at.  Nothing happens unless a Debugger has been installed (the CLI does this
+//! for `--step` and `--break-at`), and checking for one is a single atomic load.
+
+use std::{
+    collections::HashMap,
+    fmt::{Debug, Display},
+    io::{self, BufRead, Write},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+};
+
+const HELP: &str = "enter: step, c: continue to the next break, r: run to the end, d: dump state, \
+                    q: quit";
+
+/// What happens after a checkpoint.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Resume {
+    /// Carry on, pausing again when stepping or at a break.
+    Continue,
+    /// Run to the end without pausing again, after `r` or the end of input.
+    Detach,
+    /// The person debugging asked to quit, with `q`.
+    Quit,
+}
+
+/// Where to pause, and where to talk to the person debugging.
+pub struct Debugger {
+    /// Pause at every checkpoint.
+    stepping: bool,
+    /// Pause at the nth time (counting from 1) each label is reached.
+    breaks: Vec<(String, usize)>,
+    /// How many times each label has been reached.
+    hits: HashMap<String, usize>,
+    input: Box<dyn BufRead + Send>,
+    output: Box<dyn Write + Send>,
+    /// Called by an installed debugger on Resume::Quit.
+    on_quit: Box<dyn Fn() + Send>,
+}
+
+impl Debugger {
+    pub fn new(input: impl BufRead + Send + 'static, output: impl Write + Send + 'static) -> Self {
+        Self {
+            stepping: false,
+            breaks: vec![],
+            hits: HashMap::new(),
+            input: Box::new(input),
+            output: Box::new(output),
+            on_quit: Box::new(|| {}),
+        }
+    }
+
+    /// Read commands from stdin and prompt on stderr, leaving stdout for the answer.
+    pub fn stdio() -> Self {
+        Self::new(io::BufReader::new(io::stdin()), io::stderr())
+    }
+
+    /// Pause at every checkpoint from the start.
+    pub fn stepping(mut self, stepping: bool) -> Self {
+        self.stepping = stepping;
+        self
+    }
+
+    /// Pause the nth time (counting from 1) the checkpoint `label` is reached.
+    pub fn break_at(mut self, label: impl Into<String>, n: usize) -> Self {
+        self.breaks.push((label.into(), n));
+        self
+    }
+
+    /// What to do when the person debugging quits, eg. exit the program.  Without this, quitting
+    /// just stops pausing and lets the solution run to the end.
+    pub fn on_quit(mut self, on_quit: impl Fn() + Send + 'static) -> Self {
+        self.on_quit = Box::new(on_quit);
+        self
+    }
+
+    /// The number of times `label` has been reached.
+    pub fn hits(&self, label: &str) -> usize {
+        self.hits.get(label).copied().unwrap_or(0)
+    }
+
+    /// Reach a checkpoint, pausing if stepping or at a break.  `dump` describes the state, and is
+    /// only called if asked for.
+    pub fn checkpoint(&mut self, label: &str, dump: &dyn Fn() -> String) -> Resume {
+        let hits = self.hits.entry(label.to_string()).or_default();
+        *hits += 1;
+        let n = *hits;
+        let at_break = self.breaks.iter().any(|(l, b)| l == label && *b == n);
+        if !self.stepping && !at_break {
+            return Resume::Continue;
+        }
+
+        loop {
+            write!(self.output, "[{label} #{n}] > ").unwrap();
+            self.output.flush().unwrap();
+            let mut line = String::new();
+            if self.input.read_line(&mut line).unwrap_or(0) == 0 {
+                // nobody's there to answer, so stop asking
+                writeln!(self.output).unwrap();
+                return Resume::Detach;
+            }
+            match line.trim() {
+                "" | "s" | "n" => {
+                    self.stepping = true;
+                    return Resume::Continue;
+                }
+                "c" => {
+                    self.stepping = false;
+                    return Resume::Continue;
+                }
+                "r" => return Resume::Detach,
+                "d" => writeln!(self.output, "{}", dump()).unwrap(),
+                "q" => {
+                    writeln!(self.output, "quitting from the debugger").unwrap();
+                    return Resume::Quit;
+                }
+                _ => writeln!(self.output, "{HELP}").unwrap(),
+            }
+        }
+    }
+}
+
+/// Read a `--break-at` argument like `solve:3` into a label and a count.
+pub fn parse_break(text: &str) -> Option<(String, usize)> {
+    let (label, n) = text.rsplit_once(':')?;
+    Some((label.to_string(), n.parse().ok().filter(|&n| n > 0)?))
+}
+
+static ACTIVE: AtomicBool = AtomicBool::new(false);
+static DEBUGGER: Mutex<Option<Debugger>> = Mutex::new(None);
+
+/// Use `debugger` for every checkpoint from now on.
+pub fn install(debugger: Debugger) {
+    *DEBUGGER.lock().unwrap() = Some(debugger);
+    ACTIVE.store(true, Ordering::Relaxed);
+}
+
+fn reach(label: &str, dump: &dyn Fn() -> String) {
+    if !ACTIVE.load(Ordering::Relaxed) {
+        return;
+    }
+    let mut installed = DEBUGGER.lock().unwrap();
+    let Some(debugger) = installed.as_mut() else {
+        return;
+    };
+    let resume = debugger.checkpoint(label, dump);
+    if resume == Resume::Continue {
+        return;
+    }
+    let debugger = installed.take().unwrap();
+    ACTIVE.store(false, Ordering::Relaxed);
+    drop(installed);
+    if resume == Resume::Quit {
+        (debugger.on_quit)();
+    }
+}
+
+/// A checkpoint whose state is dumped with its pretty Debug output.
+pub fn checkpoint(label: &str, state: &impl Debug) {
+    reach(label, &|| format!("{state:#?}"));
+}
+
+/// A checkpoint whose state is dumped with its Display output, eg. for a Grid.
+pub fn checkpoint_display(label: &str, state: &impl Display) {
+    reach(label, &|| state.to_string());
+}
+
+#[cfg(test)]
+mod debugger_tests {
+    use super::*;
+    use std::sync::Arc;
+
+    /// Collects the debugger's output where the test can read it.
+    #[derive(Clone, Default)]
+    struct Output(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Output {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl Output {
+        fn text(&self) -> String {
+            String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+        }
+    }
+
+    fn debugger(commands: &str) -> (Debugger, Output) {
+        let output = Output::default();
+        let input = io::Cursor::new(commands.to_string());
+        (Debugger::new(input, output.clone()), output)
+    }
+
+    #[test]
+    fn break_at_test() {
+        let (debugger, output) = debugger("d\nc\n");
+        let mut debugger = debugger.break_at("loop", 3);
+        for i in 0..5 {
+            assert_eq!(
+                debugger.checkpoint("loop", &|| format!("i = {i}")),
+                Resume::Continue
+            );
+        }
+        assert_eq!(debugger.hits("loop"), 5);
+        assert_eq!(output.text(), "[loop #3] > i = 2\n[loop #3] > ");
+    }
+
+    #[test]
+    fn step_test() {
+        // step twice, then run to the end
+        let (debugger, output) = debugger("\ns\nr\n");
+        let mut debugger = debugger.stepping(true);
+        assert_eq!(debugger.checkpoint("a", &String::new), Resume::Continue);
+        assert_eq!(debugger.checkpoint("b", &String::new), Resume::Continue);
+        assert_eq!(debugger.checkpoint("a", &String::new), Resume::Detach);
+        assert_eq!(output.text(), "[a #1] > [b #1] > [a #2] > ");
+    }
+
+    #[test]
+    fn end_of_input_test() {
+        let (debugger, _) = debugger("x\n");
+        let mut debugger = debugger.stepping(true);
+        assert_eq!(debugger.checkpoint("a", &String::new), Resume::Detach);
+    }
+
+    #[test]
+    fn quit_test() {
+        let (debugger, output) = debugger("q\n");
+        let mut debugger = debugger.stepping(true);
+        assert_eq!(debugger.checkpoint("a", &String::new), Resume::Quit);
+        assert_eq!(output.text(), "[a #1] > quitting from the debugger\n");
+    }
+
+    #[test]
+    fn parse_break_test() {
+        assert_eq!(parse_break("solve:3"), Some(("solve".to_string(), 3)));
+        assert_eq!(parse_break("a:b:10"), Some(("a:b".to_string(), 10)));
+        assert_eq!(parse_break("solve"), None);
+        assert_eq!(parse_break("solve:0"), None);
+    }
+}
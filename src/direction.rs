@@ -1,4 +1,4 @@
-use std::fmt::Display;
+use std::{fmt::Display, str::FromStr};
 
 #[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
 pub enum CardDir {
@@ -8,6 +8,68 @@ pub enum CardDir {
     Right,
 }
 
+impl CardDir {
+    /// Every direction, clockwise from Up.
+    pub const ALL: [CardDir; 4] = [CardDir::Up, CardDir::Right, CardDir::Down, CardDir::Left];
+
+    pub fn opposite(&self) -> CardDir {
+        match self {
+            CardDir::Up => CardDir::Down,
+            CardDir::Down => CardDir::Up,
+            CardDir::Left => CardDir::Right,
+            CardDir::Right => CardDir::Left,
+        }
+    }
+
+    /// Turn a quarter turn anticlockwise.
+    pub fn turn_left(&self) -> CardDir {
+        match self {
+            CardDir::Up => CardDir::Left,
+            CardDir::Left => CardDir::Down,
+            CardDir::Down => CardDir::Right,
+            CardDir::Right => CardDir::Up,
+        }
+    }
+
+    /// Turn a quarter turn clockwise.
+    pub fn turn_right(&self) -> CardDir {
+        self.turn_left().opposite()
+    }
+
+    /// The (x, y) change from one step in this direction, with y increasing downwards.
+    pub fn delta(&self) -> (i64, i64) {
+        match self {
+            CardDir::Up => (0, -1),
+            CardDir::Down => (0, 1),
+            CardDir::Left => (-1, 0),
+            CardDir::Right => (1, 0),
+        }
+    }
+
+    pub fn is_horizontal(&self) -> bool {
+        matches!(self, CardDir::Left | CardDir::Right)
+    }
+
+    pub fn is_vertical(&self) -> bool {
+        !self.is_horizontal()
+    }
+
+    /// The new direction of travel after bouncing off a `/` mirror.
+    pub fn reflect_slash(&self) -> CardDir {
+        match self {
+            CardDir::Up => CardDir::Right,
+            CardDir::Right => CardDir::Up,
+            CardDir::Down => CardDir::Left,
+            CardDir::Left => CardDir::Down,
+        }
+    }
+
+    /// The new direction of travel after bouncing off a `\` mirror.
+    pub fn reflect_backslash(&self) -> CardDir {
+        self.reflect_slash().opposite()
+    }
+}
+
 impl Display for CardDir {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
@@ -23,7 +85,48 @@ impl Display for CardDir {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+/// The text couldn't be read as a direction.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ParseDirError(pub String);
+
+impl Display for ParseDirError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "not a direction: {:?}", self.0)
+    }
+}
+
+impl std::error::Error for ParseDirError {}
+
+/// Reads U/D/L/R, N/S/E/W (either case), arrows ^v<>, or the digits 0-3, which count clockwise
+/// from Right (0 = Right, 1 = Down, 2 = Left, 3 = Up).
+impl TryFrom<char> for CardDir {
+    type Error = ParseDirError;
+
+    fn try_from(c: char) -> Result<Self, Self::Error> {
+        match c.to_ascii_uppercase() {
+            'U' | 'N' | '^' | '3' => Ok(CardDir::Up),
+            'D' | 'S' | 'V' | '1' => Ok(CardDir::Down),
+            'L' | 'W' | '<' | '2' => Ok(CardDir::Left),
+            'R' | 'E' | '>' | '0' => Ok(CardDir::Right),
+            _ => Err(ParseDirError(c.to_string())),
+        }
+    }
+}
+
+/// Reads a single character, as TryFrom<char> does.
+impl FromStr for CardDir {
+    type Err = ParseDirError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        match (chars.next(), chars.next()) {
+            (Some(c), None) => CardDir::try_from(c).map_err(|_| ParseDirError(s.to_string())),
+            _ => Err(ParseDirError(s.to_string())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
 pub enum OrdDir {
     UpLeft,
     Up,
@@ -34,3 +137,502 @@ pub enum OrdDir {
     Down,
     DownRight,
 }
+
+impl OrdDir {
+    /// Every direction, clockwise from Up.
+    pub const ALL: [OrdDir; 8] = [
+        OrdDir::Up,
+        OrdDir::UpRight,
+        OrdDir::Right,
+        OrdDir::DownRight,
+        OrdDir::Down,
+        OrdDir::DownLeft,
+        OrdDir::Left,
+        OrdDir::UpLeft,
+    ];
+
+    /// The direction numbered `n`, counting clockwise from Right (0 = Right, 1 = DownRight, ...,
+    /// 7 = UpRight), if `n` is less than 8.
+    pub fn from_index(n: usize) -> Option<OrdDir> {
+        // ALL starts at Up, two steps anticlockwise of Right
+        (n < 8).then(|| OrdDir::ALL[(n + 2) % 8])
+    }
+
+    /// The direction whose step is (dx, dy), if both are -1, 0 or 1 and not both 0.
+    pub fn from_delta((dx, dy): (i64, i64)) -> Option<OrdDir> {
+        match (dx, dy) {
+            (-1, -1) => Some(OrdDir::UpLeft),
+            (0, -1) => Some(OrdDir::Up),
+            (1, -1) => Some(OrdDir::UpRight),
+            (-1, 0) => Some(OrdDir::Left),
+            (1, 0) => Some(OrdDir::Right),
+            (-1, 1) => Some(OrdDir::DownLeft),
+            (0, 1) => Some(OrdDir::Down),
+            (1, 1) => Some(OrdDir::DownRight),
+            _ => None,
+        }
+    }
+
+    /// The (x, y) change from one step in this direction, with y increasing downwards.
+    pub fn delta(&self) -> (i64, i64) {
+        match self {
+            OrdDir::UpLeft => (-1, -1),
+            OrdDir::Up => (0, -1),
+            OrdDir::UpRight => (1, -1),
+            OrdDir::Left => (-1, 0),
+            OrdDir::Right => (1, 0),
+            OrdDir::DownLeft => (-1, 1),
+            OrdDir::Down => (0, 1),
+            OrdDir::DownRight => (1, 1),
+        }
+    }
+
+    /// Apply a change to this direction's step which always gives another valid step.
+    fn map_delta(&self, f: impl Fn(i64, i64) -> (i64, i64)) -> OrdDir {
+        let (dx, dy) = self.delta();
+        OrdDir::from_delta(f(dx, dy)).unwrap()
+    }
+
+    pub fn opposite(&self) -> OrdDir {
+        self.map_delta(|dx, dy| (-dx, -dy))
+    }
+
+    /// Turn a quarter turn anticlockwise.
+    pub fn turn_left(&self) -> OrdDir {
+        self.map_delta(|dx, dy| (dy, -dx))
+    }
+
+    /// Turn a quarter turn clockwise.
+    pub fn turn_right(&self) -> OrdDir {
+        self.map_delta(|dx, dy| (-dy, dx))
+    }
+
+    /// Turn an eighth of a turn anticlockwise.
+    pub fn turn_left_45(&self) -> OrdDir {
+        let i = OrdDir::ALL.iter().position(|d| d == self).unwrap();
+        OrdDir::ALL[(i + 7) % 8]
+    }
+
+    /// Turn an eighth of a turn clockwise.
+    pub fn turn_right_45(&self) -> OrdDir {
+        let i = OrdDir::ALL.iter().position(|d| d == self).unwrap();
+        OrdDir::ALL[(i + 1) % 8]
+    }
+
+    pub fn is_horizontal(&self) -> bool {
+        matches!(self, OrdDir::Left | OrdDir::Right)
+    }
+
+    pub fn is_vertical(&self) -> bool {
+        matches!(self, OrdDir::Up | OrdDir::Down)
+    }
+
+    pub fn is_diagonal(&self) -> bool {
+        !self.is_horizontal() && !self.is_vertical()
+    }
+
+    /// The new direction of travel after bouncing off a `/` mirror.
+    pub fn reflect_slash(&self) -> OrdDir {
+        self.map_delta(|dx, dy| (-dy, -dx))
+    }
+
+    /// The new direction of travel after bouncing off a `\` mirror.
+    pub fn reflect_backslash(&self) -> OrdDir {
+        self.map_delta(|dx, dy| (dy, dx))
+    }
+}
+
+impl Display for OrdDir {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                OrdDir::UpLeft => "↖️",
+                OrdDir::Up => "⬆️",
+                OrdDir::UpRight => "↗️",
+                OrdDir::Left => "⬅️",
+                OrdDir::Right => "➡️",
+                OrdDir::DownLeft => "↙️",
+                OrdDir::Down => "⬇️",
+                OrdDir::DownRight => "↘️",
+            }
+        )
+    }
+}
+
+impl From<CardDir> for OrdDir {
+    fn from(dir: CardDir) -> Self {
+        match dir {
+            CardDir::Up => OrdDir::Up,
+            CardDir::Down => OrdDir::Down,
+            CardDir::Left => OrdDir::Left,
+            CardDir::Right => OrdDir::Right,
+        }
+    }
+}
+
+/// Fails for the diagonals.
+impl TryFrom<OrdDir> for CardDir {
+    type Error = OrdDir;
+
+    fn try_from(dir: OrdDir) -> Result<Self, Self::Error> {
+        match dir {
+            OrdDir::Up => Ok(CardDir::Up),
+            OrdDir::Down => Ok(CardDir::Down),
+            OrdDir::Left => Ok(CardDir::Left),
+            OrdDir::Right => Ok(CardDir::Right),
+            diagonal => Err(diagonal),
+        }
+    }
+}
+
+/// Reads U/D/L/R, N/S/E/W and their two letter combinations (UL, NE, ...) in either case.  A
+/// single character is read as a CardDir, so any text that parses as a CardDir parses as the
+/// same direction here, including the arrows ^v<> and the digits 0-3.  For digits 0-7, see
+/// from_index.
+impl FromStr for OrdDir {
+    type Err = ParseDirError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let err = || ParseDirError(s.to_string());
+        let mut chars = s.chars();
+        match (chars.next(), chars.next(), chars.next()) {
+            (Some(c), None, None) => CardDir::try_from(c).map(OrdDir::from).map_err(|_| err()),
+            (Some(a), Some(b), None) => {
+                let (ax, ay) = CardDir::try_from(a).map_err(|_| err())?.delta();
+                let (bx, by) = CardDir::try_from(b).map_err(|_| err())?.delta();
+                // exactly one of the two letters must be vertical
+                if ax * bx != 0 || ay * by != 0 || a.is_ascii_digit() || b.is_ascii_digit() {
+                    return Err(err());
+                }
+                OrdDir::from_delta((ax + bx, ay + by)).ok_or_else(err)
+            }
+            _ => Err(err()),
+        }
+    }
+}
+
+/// One of the six directions between neighbouring hexagons, in axial (q, r) coordinates where
+/// q is stored as x and r as y.  Implemented for both hexagon orientations, which share the same
+/// six axial steps but name them differently.
+pub trait HexDir: Copy + Eq + Sized + 'static {
+    /// Every direction, clockwise.
+    const ALL: [Self; 6];
+
+    /// The (q, r) change from one step in this direction.
+    fn delta(&self) -> (i64, i64);
+
+    fn index(&self) -> usize {
+        Self::ALL.iter().position(|d| d == self).unwrap()
+    }
+
+    fn opposite(&self) -> Self {
+        Self::ALL[(self.index() + 3) % 6]
+    }
+
+    /// Turn a sixth of a turn anticlockwise.
+    fn turn_left(&self) -> Self {
+        Self::ALL[(self.index() + 5) % 6]
+    }
+
+    /// Turn a sixth of a turn clockwise.
+    fn turn_right(&self) -> Self {
+        Self::ALL[(self.index() + 1) % 6]
+    }
+}
+
+/// Directions between flat-top hexagons, which are stacked in columns.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum FlatHexDir {
+    N,
+    NE,
+    SE,
+    S,
+    SW,
+    NW,
+}
+
+impl HexDir for FlatHexDir {
+    const ALL: [Self; 6] = [
+        FlatHexDir::N,
+        FlatHexDir::NE,
+        FlatHexDir::SE,
+        FlatHexDir::S,
+        FlatHexDir::SW,
+        FlatHexDir::NW,
+    ];
+
+    fn delta(&self) -> (i64, i64) {
+        match self {
+            FlatHexDir::N => (0, -1),
+            FlatHexDir::NE => (1, -1),
+            FlatHexDir::SE => (1, 0),
+            FlatHexDir::S => (0, 1),
+            FlatHexDir::SW => (-1, 1),
+            FlatHexDir::NW => (-1, 0),
+        }
+    }
+}
+
+/// Reads n, ne, se, s, sw and nw, in either case.
+impl FromStr for FlatHexDir {
+    type Err = ParseDirError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "n" => Ok(FlatHexDir::N),
+            "ne" => Ok(FlatHexDir::NE),
+            "se" => Ok(FlatHexDir::SE),
+            "s" => Ok(FlatHexDir::S),
+            "sw" => Ok(FlatHexDir::SW),
+            "nw" => Ok(FlatHexDir::NW),
+            _ => Err(ParseDirError(s.to_string())),
+        }
+    }
+}
+
+/// Directions between pointy-top hexagons, which are laid out in rows.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum PointyHexDir {
+    NE,
+    E,
+    SE,
+    SW,
+    W,
+    NW,
+}
+
+impl HexDir for PointyHexDir {
+    const ALL: [Self; 6] = [
+        PointyHexDir::NE,
+        PointyHexDir::E,
+        PointyHexDir::SE,
+        PointyHexDir::SW,
+        PointyHexDir::W,
+        PointyHexDir::NW,
+    ];
+
+    fn delta(&self) -> (i64, i64) {
+        match self {
+            PointyHexDir::NE => (1, -1),
+            PointyHexDir::E => (1, 0),
+            PointyHexDir::SE => (0, 1),
+            PointyHexDir::SW => (-1, 1),
+            PointyHexDir::W => (-1, 0),
+            PointyHexDir::NW => (0, -1),
+        }
+    }
+}
+
+/// Reads ne, e, se, sw, w and nw, in either case.
+impl FromStr for PointyHexDir {
+    type Err = ParseDirError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "ne" => Ok(PointyHexDir::NE),
+            "e" => Ok(PointyHexDir::E),
+            "se" => Ok(PointyHexDir::SE),
+            "sw" => Ok(PointyHexDir::SW),
+            "w" => Ok(PointyHexDir::W),
+            "nw" => Ok(PointyHexDir::NW),
+            _ => Err(ParseDirError(s.to_string())),
+        }
+    }
+}
+
+/// The six face directions of a cube.  x increases to the East, y to the South (matching the
+/// 2D grids, where y increases downwards), and z Up.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Dir3 {
+    East,
+    West,
+    North,
+    South,
+    Up,
+    Down,
+}
+
+impl Dir3 {
+    pub const ALL: [Dir3; 6] = [
+        Dir3::East,
+        Dir3::West,
+        Dir3::North,
+        Dir3::South,
+        Dir3::Up,
+        Dir3::Down,
+    ];
+
+    /// The (x, y, z) change from one step in this direction.
+    pub fn delta(&self) -> (i64, i64, i64) {
+        match self {
+            Dir3::East => (1, 0, 0),
+            Dir3::West => (-1, 0, 0),
+            Dir3::North => (0, -1, 0),
+            Dir3::South => (0, 1, 0),
+            Dir3::Up => (0, 0, 1),
+            Dir3::Down => (0, 0, -1),
+        }
+    }
+
+    pub fn opposite(&self) -> Dir3 {
+        match self {
+            Dir3::East => Dir3::West,
+            Dir3::West => Dir3::East,
+            Dir3::North => Dir3::South,
+            Dir3::South => Dir3::North,
+            Dir3::Up => Dir3::Down,
+            Dir3::Down => Dir3::Up,
+        }
+    }
+
+    /// The axis this direction moves along: 0 for x, 1 for y, 2 for z.
+    pub fn axis(&self) -> usize {
+        match self {
+            Dir3::East | Dir3::West => 0,
+            Dir3::North | Dir3::South => 1,
+            Dir3::Up | Dir3::Down => 2,
+        }
+    }
+}
+
+/// Reads compass names (e, east, n, north, ...), u/up and d/down in either case, or a signed
+/// axis like +x or -z.
+impl FromStr for Dir3 {
+    type Err = ParseDirError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "e" | "east" | "+x" => Ok(Dir3::East),
+            "w" | "west" | "-x" => Ok(Dir3::West),
+            "n" | "north" | "-y" => Ok(Dir3::North),
+            "s" | "south" | "+y" => Ok(Dir3::South),
+            "u" | "up" | "+z" => Ok(Dir3::Up),
+            "d" | "down" | "-z" => Ok(Dir3::Down),
+            _ => Err(ParseDirError(s.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod direction_tests {
+    use super::*;
+
+    #[test]
+    fn card_dir_turn_test() {
+        for dir in CardDir::ALL {
+            assert_eq!(dir.turn_left().turn_right(), dir);
+            assert_eq!(dir.turn_left().turn_left(), dir.opposite());
+            let (dx, dy) = dir.delta();
+            assert_eq!(dir.opposite().delta(), (-dx, -dy));
+            assert_eq!(dir.is_horizontal(), dy == 0);
+            assert_eq!(dir.reflect_slash().reflect_slash(), dir);
+        }
+        assert_eq!(CardDir::Up.turn_right(), CardDir::Right);
+        assert_eq!(CardDir::Right.reflect_slash(), CardDir::Up);
+        assert_eq!(CardDir::Right.reflect_backslash(), CardDir::Down);
+    }
+
+    #[test]
+    fn card_dir_parse_test() {
+        for (text, dir) in [
+            ("U", CardDir::Up),
+            ("s", CardDir::Down),
+            ("<", CardDir::Left),
+            ("0", CardDir::Right),
+            ("3", CardDir::Up),
+            ("v", CardDir::Down),
+        ] {
+            assert_eq!(text.parse(), Ok(dir));
+        }
+        assert!("X".parse::<CardDir>().is_err());
+        assert!("UL".parse::<CardDir>().is_err());
+    }
+
+    #[test]
+    fn ord_dir_test() {
+        for dir in OrdDir::ALL {
+            assert_eq!(dir.turn_left().turn_right(), dir);
+            assert_eq!(dir.turn_left_45().turn_left_45(), dir.turn_left());
+            assert_eq!(dir.turn_right_45().turn_right_45(), dir.turn_right());
+            assert_eq!(dir.opposite().opposite(), dir);
+            assert_eq!(OrdDir::from_delta(dir.delta()), Some(dir));
+        }
+        assert_eq!(OrdDir::UpLeft.opposite(), OrdDir::DownRight);
+        assert_eq!(OrdDir::Up.turn_right_45(), OrdDir::UpRight);
+        assert_eq!(OrdDir::UpRight.reflect_slash(), OrdDir::UpRight);
+        assert_eq!(OrdDir::UpRight.reflect_backslash(), OrdDir::DownLeft);
+        assert_eq!(OrdDir::Right.reflect_slash(), OrdDir::Up);
+        assert!(OrdDir::DownLeft.is_diagonal());
+        assert_eq!(CardDir::try_from(OrdDir::Left), Ok(CardDir::Left));
+        assert_eq!(CardDir::try_from(OrdDir::UpLeft), Err(OrdDir::UpLeft));
+    }
+
+    #[test]
+    fn hex_dir_test() {
+        fn check<H: HexDir + std::fmt::Debug>() {
+            let mut total = (0, 0);
+            for dir in H::ALL {
+                assert_eq!(dir.turn_left().turn_right(), dir);
+                assert_eq!(dir.turn_right().turn_right().turn_right(), dir.opposite());
+                let (dq, dr) = dir.delta();
+                assert_eq!(dir.opposite().delta(), (-dq, -dr));
+                total = (total.0 + dq, total.1 + dr);
+            }
+            assert_eq!(total, (0, 0));
+        }
+        check::<FlatHexDir>();
+        check::<PointyHexDir>();
+        assert_eq!(FlatHexDir::N.turn_right(), FlatHexDir::NE);
+        assert_eq!(PointyHexDir::E.turn_left(), PointyHexDir::NE);
+        assert_eq!("NE".parse(), Ok(FlatHexDir::NE));
+        assert_eq!("w".parse(), Ok(PointyHexDir::W));
+        assert!("e".parse::<FlatHexDir>().is_err());
+        assert!("n".parse::<PointyHexDir>().is_err());
+    }
+
+    #[test]
+    fn dir3_test() {
+        for dir in Dir3::ALL {
+            let (x, y, z) = dir.delta();
+            assert_eq!(dir.opposite().delta(), (-x, -y, -z));
+            assert_eq!(dir.opposite().axis(), dir.axis());
+        }
+        assert_eq!("up".parse(), Ok(Dir3::Up));
+        assert_eq!("D".parse(), Ok(Dir3::Down));
+        assert_eq!("-x".parse(), Ok(Dir3::West));
+        assert!("sideways".parse::<Dir3>().is_err());
+    }
+
+    #[test]
+    fn ord_dir_parse_test() {
+        for (text, dir) in [
+            ("UL", OrdDir::UpLeft),
+            ("ne", OrdDir::UpRight),
+            ("DL", OrdDir::DownLeft),
+            ("LD", OrdDir::DownLeft),
+            ("S", OrdDir::Down),
+        ] {
+            assert_eq!(text.parse(), Ok(dir));
+        }
+        assert!("UD".parse::<OrdDir>().is_err());
+        assert!("LL".parse::<OrdDir>().is_err());
+        assert!("4".parse::<OrdDir>().is_err());
+
+        // anything CardDir reads means the same direction here
+        for text in ["U", "s", "<", "0", "1", "3"] {
+            let card: CardDir = text.parse().unwrap();
+            assert_eq!(text.parse(), Ok(OrdDir::from(card)));
+        }
+    }
+
+    #[test]
+    fn ord_dir_from_index_test() {
+        assert_eq!(OrdDir::from_index(0), Some(OrdDir::Right));
+        assert_eq!(OrdDir::from_index(1), Some(OrdDir::DownRight));
+        assert_eq!(OrdDir::from_index(6), Some(OrdDir::Up));
+        assert_eq!(OrdDir::from_index(7), Some(OrdDir::UpRight));
+        assert_eq!(OrdDir::from_index(8), None);
+    }
+}
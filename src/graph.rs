@@ -0,0 +1,221 @@
+//! Write graphs in Graphviz's DOT language, to look at with `dot -Tsvg`.
+//!
+//! A `Dot` is built from an adjacency list: each node paired with the nodes it has edges to.
+//! Nodes and edges can be styled with hooks, eg. to fill in the start and end nodes or label each
+//! edge with the condition for taking it.
+
+use std::{
+    collections::HashSet,
+    fmt::{self, Display},
+    hash::Hash,
+    io,
+    path::Path,
+};
+
+use crate::render::{hex, Rgb};
+
+/// Graphviz attributes for a node or edge, like `[label="a<2006", color="#ff0000"]`.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Style {
+    attrs: Vec<(&'static str, String)>,
+}
+
+impl Style {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set any attribute.  Setting an attribute twice keeps the later value.
+    pub fn attr(mut self, key: &'static str, value: impl Display) -> Self {
+        self.attrs.retain(|(k, _)| *k != key);
+        self.attrs.push((key, value.to_string()));
+        self
+    }
+
+    pub fn label(self, label: impl Display) -> Self {
+        self.attr("label", label)
+    }
+
+    /// The colour of a node's outline or an edge's line.
+    pub fn color(self, color: Rgb) -> Self {
+        self.attr("color", hex(color))
+    }
+
+    /// Fill a node with a colour.
+    pub fn fill(self, color: Rgb) -> Self {
+        self.attr("style", "filled").attr("fillcolor", hex(color))
+    }
+
+    /// A node's shape, eg. `box` or `doublecircle`.
+    pub fn shape(self, shape: &str) -> Self {
+        self.attr("shape", shape)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.attrs.is_empty()
+    }
+}
+
+impl Display for Style {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.attrs.is_empty() {
+            return Ok(());
+        }
+        let attrs: Vec<String> = self
+            .attrs
+            .iter()
+            .map(|(k, v)| format!("{k}={}", quote(v)))
+            .collect();
+        write!(f, " [{}]", attrs.join(", "))
+    }
+}
+
+/// An ID in double quotes, so any text can be a node name.
+fn quote(id: &str) -> String {
+    format!("\"{}\"", id.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+type NodeStyle<'a, N> = Box<dyn Fn(&N) -> Style + 'a>;
+type EdgeStyle<'a, N> = Box<dyn Fn(&N, &N, usize) -> Style + 'a>;
+
+/// A graph ready to write as DOT.  Nodes are written in the order they first appear in the
+/// adjacency list, so the same graph always gives the same text.
+pub struct Dot<'a, N> {
+    directed: bool,
+    adjacency: Vec<(N, Vec<N>)>,
+    node_style: NodeStyle<'a, N>,
+    edge_style: EdgeStyle<'a, N>,
+}
+
+impl<'a, N: Display + Eq + Hash + Clone> Dot<'a, N> {
+    fn new<I: IntoIterator<Item = N>>(
+        directed: bool,
+        adjacency: impl IntoIterator<Item = (N, I)>,
+    ) -> Self {
+        Self {
+            directed,
+            adjacency: adjacency
+                .into_iter()
+                .map(|(node, to)| (node, to.into_iter().collect()))
+                .collect(),
+            node_style: Box::new(|_| Style::new()),
+            edge_style: Box::new(|_, _, _| Style::new()),
+        }
+    }
+
+    /// A directed graph with an edge from each node to each node in its list.
+    pub fn digraph<I: IntoIterator<Item = N>>(adjacency: impl IntoIterator<Item = (N, I)>) -> Self {
+        Self::new(true, adjacency)
+    }
+
+    /// An undirected graph.  An edge listed from both of its ends is only written once.
+    pub fn graph<I: IntoIterator<Item = N>>(adjacency: impl IntoIterator<Item = (N, I)>) -> Self {
+        Self::new(false, adjacency)
+    }
+
+    /// Style each node.
+    pub fn node_style(mut self, style: impl Fn(&N) -> Style + 'a) -> Self {
+        self.node_style = Box::new(style);
+        self
+    }
+
+    /// Style each edge.  The hook is given both ends and the edge's position in the `from`
+    /// node's list, to tell apart parallel edges.
+    pub fn edge_style(mut self, style: impl Fn(&N, &N, usize) -> Style + 'a) -> Self {
+        self.edge_style = Box::new(style);
+        self
+    }
+
+    /// Every node, including ones that only appear as the end of an edge.
+    fn nodes(&self) -> Vec<&N> {
+        let mut seen = HashSet::new();
+        self.adjacency
+            .iter()
+            .map(|(node, _)| node)
+            .chain(self.adjacency.iter().flat_map(|(_, to)| to))
+            .filter(|node| seen.insert(*node))
+            .collect()
+    }
+
+    /// Write the graph to a file.
+    pub fn write(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        std::fs::write(path, self.to_string())
+    }
+}
+
+impl<N: Display + Eq + Hash + Clone> Display for Dot<'_, N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (kind, arrow) = if self.directed {
+            ("digraph", "->")
+        } else {
+            ("graph", "--")
+        };
+        writeln!(f, "{kind} {{")?;
+        for node in self.nodes() {
+            let id = quote(&node.to_string());
+            writeln!(f, "    {id}{};", (self.node_style)(node))?;
+        }
+        let mut written = HashSet::new();
+        for (from, to) in &self.adjacency {
+            for (i, to) in to.iter().enumerate() {
+                if !self.directed && written.contains(&(to, from)) {
+                    continue;
+                }
+                written.insert((from, to));
+                let style = (self.edge_style)(from, to, i);
+                let (from, to) = (quote(&from.to_string()), quote(&to.to_string()));
+                writeln!(f, "    {from} {arrow} {to}{style};")?;
+            }
+        }
+        writeln!(f, "}}")
+    }
+}
+
+#[cfg(test)]
+mod graph_tests {
+    use super::*;
+
+    #[test]
+    fn digraph_test() {
+        let adjacency = vec![("in", vec!["px", "A"]), ("px", vec!["A", "R"])];
+        let dot = Dot::digraph(adjacency)
+            .node_style(|&node| match node {
+                "in" => Style::new().fill([0, 255, 0]),
+                "R" => Style::new().shape("box"),
+                _ => Style::new(),
+            })
+            .edge_style(|_, _, i| Style::new().label(i));
+        assert_eq!(
+            dot.to_string(),
+            "digraph {
+    \"in\" [style=\"filled\", fillcolor=\"#00ff00\"];
+    \"px\";
+    \"A\";
+    \"R\" [shape=\"box\"];
+    \"in\" -> \"px\" [label=\"0\"];
+    \"in\" -> \"A\" [label=\"1\"];
+    \"px\" -> \"A\" [label=\"0\"];
+    \"px\" -> \"R\" [label=\"1\"];
+}
+"
+        );
+    }
+
+    #[test]
+    fn graph_test() {
+        // each edge is listed from both ends
+        let adjacency = vec![(1, vec![2, 3]), (2, vec![1]), (3, vec![1, 3])];
+        let dot = Dot::graph(adjacency).to_string();
+        assert_eq!(dot.matches(" -- ").count(), 3);
+        assert!(dot.contains("\"1\" -- \"2\";"));
+        assert!(dot.contains("\"3\" -- \"3\";"));
+    }
+
+    #[test]
+    fn style_test() {
+        let style = Style::new().color([255, 0, 0]).label("a\"b").label("c");
+        assert_eq!(style.to_string(), " [color=\"#ff0000\", label=\"c\"]");
+        assert_eq!(Style::new().to_string(), "");
+        assert_eq!(quote("a\\b\"c"), "\"a\\\\b\\\"c\"");
+    }
+}
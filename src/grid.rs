@@ -1,8 +1,11 @@
-use std::fmt::Display;
+use std::{
+    fmt::Display,
+    hash::{DefaultHasher, Hash, Hasher},
+};
 
 use crate::point::Point;
 
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub struct Grid<T: Copy> {
     pub cells: Vec<Vec<T>>,
 }
@@ -24,6 +27,23 @@ impl<T: Copy> Grid<T> {
         self.width() * self.height()
     }
 
+    /// A compact 128-bit summary of the grid's contents, for remembering states without keeping
+    /// whole grids around.  Equal grids always have equal fingerprints; unequal grids are very
+    /// unlikely to.
+    pub fn fingerprint(&self) -> u128
+    where
+        T: Hash,
+    {
+        // two 64-bit hashes, kept independent by feeding each a different prefix
+        let half = |salt: u8| {
+            let mut hasher = DefaultHasher::new();
+            salt.hash(&mut hasher);
+            self.hash(&mut hasher);
+            hasher.finish()
+        };
+        ((half(0) as u128) << 64) | half(1) as u128
+    }
+
     pub fn rows(&self) -> Vec<Vec<T>> {
         self.cells.clone()
     }
@@ -146,83 +166,144 @@ where
     }
 }
 
+/// The API shared by dense and sparse grids, so helpers can be written once for both.
+pub trait GridLike<T: Copy> {
+    /// The type of a single x or y coordinate.
+    type Coord: Copy;
+    /// The type of a cell's position.
+    type Pos: Copy;
+
+    /// Get the cell data at the given position, or None if there is no cell there.
+    fn get(&self, x: Self::Coord, y: Self::Coord) -> Option<T>;
+
+    /// Get cells adjacent to the given point in the cardinal directions.  See Grid::adj_4.
+    fn adj_4(&self, x: Self::Coord, y: Self::Coord) -> Adj4<T, Self::Pos>;
+
+    /// Get cells adjacent to the given point in cardinal and ordinal directions.  See
+    /// Grid::adj_8.
+    fn adj_8(&self, x: Self::Coord, y: Self::Coord) -> Adj8<T, Self::Pos>;
+
+    /// Render the grid as text, one line per row, using `fill` for positions that have no cell.
+    fn render(&self, fill: char) -> String
+    where
+        T: Display;
+}
+
+impl<T: Copy> GridLike<T> for Grid<T> {
+    type Coord = usize;
+    type Pos = Point<2>;
+
+    fn get(&self, x: usize, y: usize) -> Option<T> {
+        Grid::get(self, x, y)
+    }
+
+    fn adj_4(&self, x: usize, y: usize) -> Adj4<T> {
+        Grid::adj_4(self, x, y)
+    }
+
+    fn adj_8(&self, x: usize, y: usize) -> Adj8<T> {
+        Grid::adj_8(self, x, y)
+    }
+
+    /// Dense grids have a cell at every position, so `fill` is never used.
+    fn render(&self, _fill: char) -> String
+    where
+        T: Display,
+    {
+        self.to_string()
+    }
+}
+
 /// A representation of cells adjacent to a point in the cardinal directions.  Produced by Grid::adj_4.
 #[derive(PartialEq, Debug)]
-pub struct Adj4<T: Copy> {
-    pub cells: [Option<Cell<T>>; 4],
+pub struct Adj4<T: Copy, P = Point<2>> {
+    pub cells: [Option<Cell<T, P>>; 4],
 }
 
-impl<T: Copy> Adj4<T> {
-    pub fn new(cells: [Option<Cell<T>>; 4]) -> Self {
+impl<T: Copy, P: Copy> Adj4<T, P> {
+    pub fn new(cells: [Option<Cell<T, P>>; 4]) -> Self {
         Self { cells }
     }
-    pub fn up(&self) -> Option<Cell<T>> {
+    pub fn up(&self) -> Option<Cell<T, P>> {
         self.cells[0]
     }
-    pub fn left(&self) -> Option<Cell<T>> {
+    pub fn left(&self) -> Option<Cell<T, P>> {
         self.cells[1]
     }
-    pub fn right(&self) -> Option<Cell<T>> {
+    pub fn right(&self) -> Option<Cell<T, P>> {
         self.cells[2]
     }
-    pub fn down(&self) -> Option<Cell<T>> {
+    pub fn down(&self) -> Option<Cell<T, P>> {
         self.cells[3]
     }
 }
 
 /// A representation of cells adjacent to a point.  Produced by Grid::adj_8.
 #[derive(PartialEq, Debug)]
-pub struct Adj8<T: Copy> {
-    pub cells: [Option<Cell<T>>; 8],
+pub struct Adj8<T: Copy, P = Point<2>> {
+    pub cells: [Option<Cell<T, P>>; 8],
 }
 
-impl<T: Copy> Adj8<T> {
-    pub fn new(cells: [Option<Cell<T>>; 8]) -> Self {
+impl<T: Copy, P: Copy> Adj8<T, P> {
+    pub fn new(cells: [Option<Cell<T, P>>; 8]) -> Self {
         Self { cells }
     }
 
-    pub fn up_left(&self) -> Option<Cell<T>> {
+    pub fn up_left(&self) -> Option<Cell<T, P>> {
         self.cells[0]
     }
-    pub fn up(&self) -> Option<Cell<T>> {
+    pub fn up(&self) -> Option<Cell<T, P>> {
         self.cells[1]
     }
-    pub fn up_right(&self) -> Option<Cell<T>> {
+    pub fn up_right(&self) -> Option<Cell<T, P>> {
         self.cells[2]
     }
-    pub fn left(&self) -> Option<Cell<T>> {
+    pub fn left(&self) -> Option<Cell<T, P>> {
         self.cells[3]
     }
-    pub fn right(&self) -> Option<Cell<T>> {
+    pub fn right(&self) -> Option<Cell<T, P>> {
         self.cells[4]
     }
-    pub fn down_left(&self) -> Option<Cell<T>> {
+    pub fn down_left(&self) -> Option<Cell<T, P>> {
         self.cells[5]
     }
-    pub fn down(&self) -> Option<Cell<T>> {
+    pub fn down(&self) -> Option<Cell<T, P>> {
         self.cells[6]
     }
-    pub fn down_right(&self) -> Option<Cell<T>> {
+    pub fn down_right(&self) -> Option<Cell<T, P>> {
         self.cells[7]
     }
 }
 
 /// A cell in a grid, containing some data and a position within the grid.
 #[derive(Eq, PartialEq, Debug, Copy, Clone)]
-pub struct Cell<T> {
-    pub pos: Point<2>,
+pub struct Cell<T, P = Point<2>> {
+    pub pos: P,
     pub data: T,
 }
 
-impl<T> Cell<T> {
-    pub fn new(pos: Point<2>, data: T) -> Self {
+impl<T, P> Cell<T, P> {
+    pub fn new(pos: P, data: T) -> Self {
         Self { pos, data }
     }
 }
 
+#[cfg(test)]
+impl Grid<char> {
+    /// A grid of characters, one row per string, for writing test grids as pictures.
+    pub(crate) fn from_rows(rows: impl IntoIterator<Item = impl AsRef<str>>) -> Self {
+        Self::new(
+            rows.into_iter()
+                .map(|row| row.as_ref().chars().collect())
+                .collect(),
+        )
+    }
+}
+
 #[cfg(test)]
 mod grid_tests {
     use super::*;
+    use crate::grid_diff::assert_grid_eq;
 
     #[test]
     fn empty_test() {
@@ -306,13 +387,13 @@ mod grid_tests {
         g.set_col(1, &[13, 11, 12]);
 
         #[rustfmt::skip]
-        assert_eq!(
-            g.cells,
-            vec![
+        assert_grid_eq(
+            &g,
+            &Grid::new(vec![
                 vec![1, 13, 3],
                 vec![4, 11, 6],
                 vec![7, 12, 9]
-            ]
+            ]),
         );
     }
 
@@ -330,13 +411,21 @@ mod grid_tests {
         g.set_row(1, &[13, 11, 12]);
 
         #[rustfmt::skip]
-        assert_eq!(
-            g.cells,
-            vec![
+        assert_grid_eq(
+            &g,
+            &Grid::new(vec![
                 vec![ 1,  2,  3],
                 vec![13, 11, 12],
                 vec![ 7,  8,  9]
-            ]
+            ]),
         );
     }
+
+    #[test]
+    fn grid_fingerprint_test() {
+        let a: Grid<u8> = Grid::new(vec![vec![1, 2], vec![3, 4]]);
+        let b: Grid<u8> = Grid::new(vec![vec![1, 2], vec![4, 3]]);
+        assert_eq!(a.fingerprint(), a.clone().fingerprint());
+        assert_ne!(a.fingerprint(), b.fingerprint());
+    }
 }
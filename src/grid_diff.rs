@@ -0,0 +1,224 @@
+//! Compare two grids cell by cell, and show the differences in the terminal.
+
+use std::fmt::Display;
+
+use crate::{grid::Grid, point::Point};
+
+const HIGHLIGHT: &str = "\x1b[30;41m";
+const RESET: &str = "\x1b[0m";
+
+/// A cell whose data differs between two grids.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct CellChange<T> {
+    pub pos: Point<2>,
+    pub old: T,
+    pub new: T,
+}
+
+/// The differences between an old grid and a new grid.  Only the area the two grids share is
+/// compared cell by cell; if their sizes differ, that's reported separately.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct GridDiff<T> {
+    pub changes: Vec<CellChange<T>>,
+    /// (width, height) of the old and new grids.
+    pub old_size: (usize, usize),
+    pub new_size: (usize, usize),
+}
+
+impl<T> GridDiff<T> {
+    /// True if the grids are the same size and every cell matches.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty() && self.old_size == self.new_size
+    }
+
+    pub fn is_changed(&self, pos: Point<2>) -> bool {
+        self.changes.iter().any(|change| change.pos == pos)
+    }
+}
+
+impl<T: Display> Display for GridDiff<T> {
+    /// List each change on its own line, like `(3, 1): # -> .`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.old_size != self.new_size {
+            writeln!(
+                f,
+                "size: {}x{} -> {}x{}",
+                self.old_size.0, self.old_size.1, self.new_size.0, self.new_size.1
+            )?;
+        }
+        for change in &self.changes {
+            writeln!(f, "{}: {} -> {}", change.pos, change.old, change.new)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: Copy + PartialEq> Grid<T> {
+    /// Find the cells that differ between this grid and `other`, in reading order.
+    pub fn diff(&self, other: &Grid<T>) -> GridDiff<T> {
+        let mut changes = vec![];
+        for (y, (old_row, new_row)) in self.cells.iter().zip(&other.cells).enumerate() {
+            for (x, (old, new)) in old_row.iter().zip(new_row).enumerate() {
+                if old != new {
+                    changes.push(CellChange {
+                        pos: [x, y].into(),
+                        old: *old,
+                        new: *new,
+                    });
+                }
+            }
+        }
+        GridDiff {
+            changes,
+            old_size: (self.width(), self.height()),
+            new_size: (other.width(), other.height()),
+        }
+    }
+}
+
+impl<T: Copy + PartialEq + Display> Grid<T> {
+    /// Print `other` with the cells that differ from this grid highlighted in colour.
+    pub fn diff_highlighted(&self, other: &Grid<T>) -> String {
+        let diff = self.diff(other);
+        let mut out = String::new();
+        for (y, row) in other.cells.iter().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                let changed = diff.is_changed([x, y].into()) || self.get(x, y).is_none();
+                if changed {
+                    out.push_str(&format!("{HIGHLIGHT}{cell}{RESET}"));
+                } else {
+                    out.push_str(&cell.to_string());
+                }
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    /// Print this grid and `other` next to each other.  With `color`, changed cells are
+    /// highlighted on both sides; without it, a third column marks them with `^`.
+    pub fn diff_side_by_side(&self, other: &Grid<T>, color: bool) -> String {
+        let diff = self.diff(other);
+        let cell_width = self
+            .cells
+            .iter()
+            .chain(&other.cells)
+            .flatten()
+            .map(|t| t.to_string().chars().count())
+            .max()
+            .unwrap_or(1);
+
+        // render one row of a grid, padding missing cells so the columns stay aligned
+        let render_row = |grid: &Grid<T>, y: usize, width: usize| -> String {
+            (0..width)
+                .map(|x| {
+                    let text = grid.get(x, y).map(|t| t.to_string()).unwrap_or_default();
+                    let padded = format!("{text:cell_width$}");
+                    if color && (diff.is_changed([x, y].into()) || grid.get(x, y).is_none()) {
+                        format!("{HIGHLIGHT}{padded}{RESET}")
+                    } else {
+                        padded
+                    }
+                })
+                .collect()
+        };
+
+        let width = self.width().max(other.width());
+        let height = self.height().max(other.height());
+        let mut out = String::new();
+        for y in 0..height {
+            out.push_str(&render_row(self, y, width));
+            out.push_str(" | ");
+            out.push_str(&render_row(other, y, width));
+            if !color {
+                out.push_str(" | ");
+                for x in 0..width {
+                    let changed = diff.is_changed([x, y].into())
+                        || self.get(x, y).is_none() != other.get(x, y).is_none();
+                    let mark = if changed { "^" } else { " " };
+                    out.push_str(&format!("{mark:cell_width$}"));
+                }
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// Assert that two grids are equal, printing them side by side along with a list of the changed
+/// cells if they aren't.
+#[track_caller]
+pub fn assert_grid_eq<T: Copy + PartialEq + Display>(actual: &Grid<T>, expected: &Grid<T>) {
+    let diff = expected.diff(actual);
+    if !diff.is_empty() {
+        panic!(
+            "grids differ (expected | actual | changes)\n{}\n{}",
+            expected.diff_side_by_side(actual, false),
+            diff
+        );
+    }
+}
+
+#[cfg(test)]
+mod grid_diff_tests {
+    use super::*;
+
+    #[test]
+    fn diff_test() {
+        let old = Grid::from_rows(["O.#", "..O"]);
+        let new = Grid::from_rows(["..#", "O.O"]);
+        let diff = old.diff(&new);
+        assert_eq!(
+            diff.changes,
+            vec![
+                CellChange {
+                    pos: [0, 0].into(),
+                    old: 'O',
+                    new: '.'
+                },
+                CellChange {
+                    pos: [0, 1].into(),
+                    old: '.',
+                    new: 'O'
+                },
+            ]
+        );
+        assert_eq!(diff.to_string(), "(0, 0): O -> .\n(0, 1): . -> O\n");
+        assert!(old.diff(&old).is_empty());
+    }
+
+    #[test]
+    fn diff_size_test() {
+        let old = Grid::from_rows(["ab", "cd"]);
+        let new = Grid::from_rows(["ab"]);
+        let diff = old.diff(&new);
+        assert!(diff.changes.is_empty());
+        assert!(!diff.is_empty());
+    }
+
+    #[test]
+    fn diff_side_by_side_test() {
+        let old = Grid::from_rows(["O.#", "..O"]);
+        let new = Grid::from_rows(["..#", "..O"]);
+        assert_eq!(
+            old.diff_side_by_side(&new, false),
+            "O.# | ..# | ^  \n..O | ..O |    \n"
+        );
+    }
+
+    #[test]
+    fn diff_highlighted_test() {
+        let old = Grid::from_rows(["ab"]);
+        let new = Grid::from_rows(["aX"]);
+        assert_eq!(
+            old.diff_highlighted(&new),
+            format!("a{HIGHLIGHT}X{RESET}\n")
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "(1, 0): b -> X")]
+    fn assert_grid_eq_test() {
+        assert_grid_eq(&Grid::from_rows(["aX"]), &Grid::from_rows(["ab"]));
+    }
+}
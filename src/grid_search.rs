@@ -0,0 +1,306 @@
+//! Graph searches over the cells of a Grid, treating 4-adjacent cells as connected.
+
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, VecDeque},
+    hash::Hash,
+};
+
+use pathfinding::prelude::astar;
+
+use crate::{
+    grid::{Cell, Grid},
+    point::Point,
+};
+
+/// The outcome of a single-source search: the distance to every reached cell, and the previous
+/// cell on a shortest path to it.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Distances {
+    pub start: Point<2>,
+    /// Distance from the start to each cell, or None if the cell wasn't reached.
+    pub dist: Grid<Option<usize>>,
+    /// The cell before each cell on a shortest path from the start.
+    pub prev: Grid<Option<Point<2>>>,
+}
+
+impl Distances {
+    /// Nothing reached yet but the start, or nothing at all if the start is outside the grid.
+    fn new<T: Copy>(grid: &Grid<T>, start: Point<2>) -> Self {
+        let mut dist = Grid::new(vec![vec![None; grid.width()]; grid.height()]);
+        if let Some(d) = dist
+            .cells
+            .get_mut(start.y())
+            .and_then(|row| row.get_mut(start.x()))
+        {
+            *d = Some(0);
+        }
+        Self {
+            start,
+            dist,
+            prev: Grid::new(vec![vec![None; grid.width()]; grid.height()]),
+        }
+    }
+
+    /// Distance from the start to the given point, or None if it wasn't reached.
+    pub fn distance(&self, to: Point<2>) -> Option<usize> {
+        self.dist.get(to.x(), to.y()).flatten()
+    }
+
+    /// Reconstruct a shortest path from the start to the given point, including both ends.
+    pub fn path_to(&self, to: Point<2>) -> Option<Vec<Point<2>>> {
+        self.distance(to)?;
+        let mut path = vec![to];
+        let mut p = to;
+        while let Some(prev) = self.prev.cells[p.y()][p.x()] {
+            path.push(prev);
+            p = prev;
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+impl<T: Copy> Grid<T> {
+    fn cell(&self, p: Point<2>) -> Cell<T> {
+        Cell::new(p, self.cells[p.y()][p.x()])
+    }
+
+    /// Breadth-first search from `start`, where each step costs 1.  `passable` is given the cell
+    /// being stepped from and the cell being stepped to, and decides whether the step is allowed.
+    /// A start outside the grid reaches nothing.
+    pub fn bfs_distances(
+        &self,
+        start: Point<2>,
+        passable: impl Fn(Cell<T>, Cell<T>) -> bool,
+    ) -> Distances {
+        let mut result = Distances::new(self, start);
+        let mut queue: VecDeque<_> = result.distance(start).map(|_| start).into_iter().collect();
+
+        while let Some(p) = queue.pop_front() {
+            let from = self.cell(p);
+            let d = result.dist.cells[p.y()][p.x()].unwrap();
+            for to in self.adj_4(p.x(), p.y()).cells.into_iter().flatten() {
+                let seen = &mut result.dist.cells[to.pos.y()][to.pos.x()];
+                if seen.is_none() && passable(from, to) {
+                    *seen = Some(d + 1);
+                    result.prev.cells[to.pos.y()][to.pos.x()] = Some(p);
+                    queue.push_back(to.pos);
+                }
+            }
+        }
+
+        result
+    }
+
+    /// Dijkstra's algorithm from `start`.  `cost` is given the cell being stepped from and the
+    /// cell being stepped to, and returns the cost of the step, or None if it isn't allowed.  A
+    /// start outside the grid reaches nothing.
+    pub fn dijkstra(
+        &self,
+        start: Point<2>,
+        cost: impl Fn(Cell<T>, Cell<T>) -> Option<usize>,
+    ) -> Distances {
+        let mut result = Distances::new(self, start);
+        let mut heap: BinaryHeap<_> = result
+            .distance(start)
+            .map(|d| Reverse((d, start)))
+            .into_iter()
+            .collect();
+
+        while let Some(Reverse((d, p))) = heap.pop() {
+            if result.dist.cells[p.y()][p.x()].is_some_and(|best| best < d) {
+                continue;
+            }
+            let from = self.cell(p);
+            for to in self.adj_4(p.x(), p.y()).cells.into_iter().flatten() {
+                if let Some(step) = cost(from, to) {
+                    let new_d = d + step;
+                    let best = &mut result.dist.cells[to.pos.y()][to.pos.x()];
+                    if best.is_none_or(|best| new_d < best) {
+                        *best = Some(new_d);
+                        result.prev.cells[to.pos.y()][to.pos.x()] = Some(p);
+                        heap.push(Reverse((new_d, to.pos)));
+                    }
+                }
+            }
+        }
+
+        result
+    }
+
+    /// A* search through arbitrary states layered over the grid, for puzzles where the position
+    /// alone doesn't determine which moves are legal (eg, a heading or a step counter).
+    /// `successors` is given the grid and a state, and returns the next states with their costs.
+    /// Returns the states along the cheapest path, including the start, and the total cost.
+    pub fn astar_with_state<S, FN, IN, FH, FS>(
+        &self,
+        start: &S,
+        mut successors: FN,
+        heuristic: FH,
+        success: FS,
+    ) -> Option<(Vec<S>, usize)>
+    where
+        S: Eq + Hash + Clone,
+        FN: FnMut(&Self, &S) -> IN,
+        IN: IntoIterator<Item = (S, usize)>,
+        FH: Fn(&S) -> usize,
+        FS: Fn(&S) -> bool,
+    {
+        astar(start, |s| successors(self, s), heuristic, success)
+    }
+
+    /// Find every cell reachable from `start` through 4-adjacent cells for which `include`
+    /// returns true.  The start is always included, unless it's outside the grid.
+    pub fn flood_fill(&self, start: Point<2>, include: impl Fn(T) -> bool) -> Vec<Point<2>> {
+        self.bfs_distances(start, |_, to| include(to.data))
+            .dist
+            .cells
+            .iter()
+            .enumerate()
+            .flat_map(|(y, row)| {
+                row.iter()
+                    .enumerate()
+                    .filter(|(_, d)| d.is_some())
+                    .map(move |(x, _)| [x, y].into())
+            })
+            .collect()
+    }
+
+    /// Label groups of 4-adjacent cells for which `same` returns true.  Returns a grid of labels
+    /// (numbered from 0 in reading order of each group's first cell) and the number of groups.
+    pub fn connected_components(&self, same: impl Fn(T, T) -> bool) -> (Grid<usize>, usize) {
+        let mut labels: Vec<Vec<Option<usize>>> = vec![vec![None; self.width()]; self.height()];
+        let mut count = 0;
+
+        for y in 0..self.height() {
+            for x in 0..self.width() {
+                if labels[y][x].is_some() {
+                    continue;
+                }
+                labels[y][x] = Some(count);
+                let mut queue = VecDeque::from([Point::from([x, y])]);
+                while let Some(p) = queue.pop_front() {
+                    let from = self.cell(p);
+                    for to in self.adj_4(p.x(), p.y()).cells.into_iter().flatten() {
+                        let label = &mut labels[to.pos.y()][to.pos.x()];
+                        if label.is_none() && same(from.data, to.data) {
+                            *label = Some(count);
+                            queue.push_back(to.pos);
+                        }
+                    }
+                }
+                count += 1;
+            }
+        }
+
+        (
+            Grid::new(
+                labels
+                    .into_iter()
+                    .map(|row| row.into_iter().map(Option::unwrap).collect())
+                    .collect(),
+            ),
+            count,
+        )
+    }
+}
+
+#[cfg(test)]
+mod grid_search_tests {
+    use super::*;
+
+    fn maze() -> Grid<char> {
+        #[rustfmt::skip]
+        let cells = [
+            "..#.",
+            ".##.",
+            "....",
+        ];
+        Grid::from_rows(cells)
+    }
+
+    #[test]
+    fn bfs_distances_test() {
+        let g = maze();
+        let d = g.bfs_distances([0, 0].into(), |_, to| to.data == '.');
+        assert_eq!(d.distance([3, 0].into()), Some(7));
+        assert_eq!(d.distance([2, 0].into()), None);
+        assert_eq!(
+            d.path_to([1, 2].into()),
+            Some(vec![
+                [0, 0].into(),
+                [0, 1].into(),
+                [0, 2].into(),
+                [1, 2].into()
+            ])
+        );
+    }
+
+    #[test]
+    fn dijkstra_test() {
+        let g = Grid::new(vec![vec![1, 9, 1], vec![1, 1, 1]]);
+        let d = g.dijkstra([0, 0].into(), |_, to| Some(to.data));
+        assert_eq!(d.distance([2, 0].into()), Some(4));
+        assert_eq!(d.path_to([2, 0].into()).unwrap().len(), 5);
+    }
+
+    #[test]
+    fn flood_fill_test() {
+        let g = maze();
+        assert_eq!(g.flood_fill([0, 0].into(), |c| c == '.').len(), 9);
+        assert_eq!(g.flood_fill([2, 0].into(), |c| c == '#').len(), 3);
+    }
+
+    #[test]
+    fn start_outside_grid_test() {
+        let g = maze();
+        let outside = [4, 0].into();
+        let bfs = g.bfs_distances(outside, |_, _| true);
+        assert_eq!(bfs.distance(outside), None);
+        assert_eq!(bfs.distance([3, 0].into()), None);
+        let dijkstra = g.dijkstra([0, 3].into(), |_, _| Some(1));
+        assert_eq!(dijkstra.distance([0, 2].into()), None);
+        assert!(g.flood_fill([9, 9].into(), |_| true).is_empty());
+    }
+
+    #[test]
+    fn connected_components_test() {
+        let g = maze();
+        let (labels, count) = g.connected_components(|a, b| a == b);
+        assert_eq!(count, 2);
+        #[rustfmt::skip]
+        assert_eq!(
+            labels.cells,
+            vec![
+                vec![0, 0, 1, 0],
+                vec![0, 1, 1, 0],
+                vec![0, 0, 0, 0],
+            ]
+        );
+    }
+
+    #[test]
+    fn astar_with_state_test() {
+        // limit how many steps can be taken in a row
+        let g = Grid::new(vec![vec![1; 4]; 1]);
+        let search = |max_run| {
+            g.astar_with_state(
+                &([0, 0].into(), 0),
+                |g: &Grid<i32>, (p, run): &(Point<2>, usize)| {
+                    g.adj_4(p.x(), p.y())
+                        .right()
+                        .filter(|_| *run < max_run)
+                        .map(|c| ((c.pos, run + 1), 1))
+                },
+                |(p, _)| 3 - p.x(),
+                |(p, _)| p.x() == 3,
+            )
+        };
+        assert_eq!(search(2), None);
+        assert_eq!(
+            search(3).map(|(path, cost)| (path.len(), cost)),
+            Some((4, 3))
+        );
+    }
+}
@@ -0,0 +1,115 @@
+//! Assign ids to states as they're seen, to detect exact repeats in simulations.
+
+use std::{collections::HashMap, hash::Hash};
+
+/// Hands out sequential ids to distinct states, and remembers the state behind each id.
+#[derive(Debug, Clone)]
+pub struct Interner<S> {
+    ids: HashMap<S, usize>,
+    states: Vec<S>,
+}
+
+/// A repeating run of states: the states from step `start` onward repeat every `len` steps.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Cycle {
+    pub start: usize,
+    pub len: usize,
+}
+
+impl Cycle {
+    /// Find the earliest step whose state matches the state at step `n`.
+    pub fn equivalent_step(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.len
+        }
+    }
+}
+
+impl<S: Hash + Eq + Clone> Default for Interner<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: Hash + Eq + Clone> Interner<S> {
+    pub fn new() -> Self {
+        Self {
+            ids: HashMap::new(),
+            states: vec![],
+        }
+    }
+
+    /// Get the id of a state, assigning the next id if it hasn't been seen before.  The bool is
+    /// true if the state is new.
+    pub fn intern(&mut self, state: S) -> (usize, bool) {
+        if let Some(&id) = self.ids.get(&state) {
+            return (id, false);
+        }
+        let id = self.states.len();
+        self.ids.insert(state.clone(), id);
+        self.states.push(state);
+        (id, true)
+    }
+
+    /// Record the state of the next step of a simulation, where step n is the nth state
+    /// recorded.  Returns the cycle if this state has been seen before.
+    pub fn find_cycle(&mut self, state: S) -> Option<Cycle> {
+        let step = self.states.len();
+        match self.intern(state) {
+            (start, false) => Some(Cycle {
+                start,
+                len: step - start,
+            }),
+            (_, true) => None,
+        }
+    }
+
+    /// The state with the given id.
+    pub fn get(&self, id: usize) -> Option<&S> {
+        self.states.get(id)
+    }
+
+    pub fn id_of(&self, state: &S) -> Option<usize> {
+        self.ids.get(state).copied()
+    }
+
+    /// The number of distinct states seen.
+    pub fn len(&self) -> usize {
+        self.states.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.states.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod interner_tests {
+    use super::*;
+
+    #[test]
+    fn intern_test() {
+        let mut interner = Interner::new();
+        assert_eq!(interner.intern("a"), (0, true));
+        assert_eq!(interner.intern("b"), (1, true));
+        assert_eq!(interner.intern("a"), (0, false));
+        assert_eq!(interner.get(1), Some(&"b"));
+        assert_eq!(interner.len(), 2);
+    }
+
+    #[test]
+    fn find_cycle_test() {
+        // 0 1 2 3 4 2 3 4 2 ...
+        let mut interner = Interner::new();
+        let cycle = (0..)
+            .map(|i| if i < 2 { i } else { 2 + (i - 2) % 3 })
+            .find_map(|state| interner.find_cycle(state))
+            .unwrap();
+        assert_eq!(cycle, Cycle { start: 2, len: 3 });
+        assert_eq!(cycle.equivalent_step(1), 1);
+        assert_eq!(cycle.equivalent_step(9), 3);
+        assert_eq!(interner.get(cycle.equivalent_step(1_000)), Some(&4));
+    }
+}
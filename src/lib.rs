@@ -1,5 +1,7 @@
 #![allow(clippy::let_and_return)]
 #![allow(unused)]
+pub mod aabb;
+pub mod bit_grid;
 pub mod d1;
 pub mod d10;
 pub mod d11;
@@ -25,6 +27,19 @@ pub mod d6;
 pub mod d7;
 pub mod d8;
 pub mod d9;
+pub mod debugger;
 pub mod direction;
+pub mod graph;
 pub mod grid;
+pub mod grid_diff;
+pub mod grid_search;
+pub mod interner;
+pub mod pathfinder;
 pub mod point;
+pub mod ray;
+pub mod record;
+pub mod regions;
+pub mod render;
+pub mod sparse_grid;
+pub mod tiled_grid;
+pub mod visualize;
@@ -1,6 +1,6 @@
 mod args;
 
-use std::{fs::read_to_string, process::exit};
+use std::fs::read_to_string;
 
 fn main() {
     let args = args::parse_args().unwrap_or_else(|_| {
@@ -9,6 +9,17 @@ fn main() {
         std::process::exit(1);
     });
 
+    if args.dot.is_some() && args.day == 255 {
+        eprintln!("Error: --dot writes one day's graph, so it can't be used with day 255");
+        exit(1);
+    }
+    if let Some(path) = &args.record {
+        start_recording(path, &args);
+    }
+    if args.step || !args.break_at.is_empty() {
+        start_debugger(&args);
+    }
+
     // day 255 is a magic day number meaning "run all days"
     if args.day == 255 {
         for day in 1..=25 {
@@ -29,6 +40,9 @@ fn main() {
         };
 
         if let Ok(input) = read_to_string(input_file) {
+            if let Some(path) = &args.dot {
+                write_dot(args.day, &input, path);
+            }
             run(args.day, args.part, input);
         } else {
             eprintln!(
@@ -39,6 +53,71 @@ fn main() {
     } else {
         eprintln!("Error: DAY must be 1 through 25, or 255 to run all days");
     }
+
+    aoc2023::visualize::finish_recording();
+}
+
+/// Exit with an error code, first flushing any recording so the frames so far aren't lost.
+fn exit(code: i32) -> ! {
+    aoc2023::visualize::finish_recording();
+    std::process::exit(code)
+}
+
+fn start_recording(path: &str, args: &args::Args) {
+    if cfg!(not(feature = "visualize")) {
+        eprintln!("Error: --record requires the visualize feature (try `just viz`)");
+        exit(1);
+    }
+    let options = aoc2023::record::RecordOptions {
+        every: args.record_every.max(1),
+        max_duration: args.max_duration.map(std::time::Duration::from_secs_f64),
+        ..Default::default()
+    };
+    if let Err(e) = aoc2023::visualize::record_to(path, options) {
+        eprintln!("Error: couldn't start recording to {path}: {e}");
+        exit(1);
+    }
+}
+
+fn start_debugger(args: &args::Args) {
+    let mut debugger = aoc2023::debugger::Debugger::stdio()
+        .stepping(args.step)
+        .on_quit(|| exit(1));
+    for text in &args.break_at {
+        let Some((label, n)) = aoc2023::debugger::parse_break(text) else {
+            eprintln!("Error: --break-at takes <label>:<n>, like solve:3");
+            exit(1);
+        };
+        debugger = debugger.break_at(label, n);
+    }
+    aoc2023::debugger::install(debugger);
+}
+
+fn write_dot(day: u8, input: &str, path: &str) {
+    let written = match day {
+        8 => {
+            let model = aoc2023::d8::parse(input.to_string());
+            let graph = aoc2023::d8::graph(&model);
+            graph.write(path)
+        }
+        19 => {
+            let (workflows, _) = aoc2023::d19::parse(input.to_string());
+            let graph = aoc2023::d19::graph(&workflows);
+            graph.write(path)
+        }
+        20 | 23 | 25 => {
+            eprintln!("Error: day {day} isn't solved yet, so it has no graph for --dot");
+            exit(1);
+        }
+        _ => {
+            eprintln!("Error: day {day} has no graph for --dot");
+            exit(1);
+        }
+    };
+    if let Err(e) = written {
+        eprintln!("Error: couldn't write {path}: {e}");
+        exit(1);
+    }
 }
 
 fn run(day: u8, part: u8, input: String) {
@@ -0,0 +1,318 @@
+//! Shortest path searches over a Grid where the legal moves depend on more than just position.
+//!
+//! A rule object implementing `Rules` decides what a search state looks like, which states can
+//! follow it, and what each move costs.  `Grid::find_path` then runs A* over those states.
+
+use std::hash::Hash;
+
+use crate::{direction::CardDir, grid::Grid, point::Point};
+
+/// Movement rules for a search over a grid.
+pub trait Rules<T: Copy> {
+    /// Everything needed to decide which moves are legal next (position, heading, etc).
+    type State: Eq + Hash + Clone;
+
+    /// The states reachable in one move from `state`, with the cost of each move.
+    fn successors(&self, grid: &Grid<T>, state: &Self::State) -> Vec<(Self::State, usize)>;
+
+    /// An estimate of the remaining cost which must never overestimate it.  Defaults to 0, which
+    /// turns the search into Dijkstra's algorithm.
+    fn heuristic(&self, _grid: &Grid<T>, _state: &Self::State) -> usize {
+        0
+    }
+
+    /// Whether the search can stop at `state`.
+    fn is_goal(&self, grid: &Grid<T>, state: &Self::State) -> bool;
+}
+
+impl<T: Copy> Grid<T> {
+    /// Find the cheapest path from `start` to a goal state under the given rules.  Returns the
+    /// states along the path, including the start, and the total cost.
+    pub fn find_path<R: Rules<T>>(
+        &self,
+        rules: &R,
+        start: R::State,
+    ) -> Option<(Vec<R::State>, usize)> {
+        self.find_path_watched(rules, start, |_| {})
+    }
+
+    /// Like find_path, but calls `expanded` with each state as the search expands it, to watch
+    /// the search's frontier grow.
+    pub fn find_path_watched<R: Rules<T>>(
+        &self,
+        rules: &R,
+        start: R::State,
+        mut expanded: impl FnMut(&R::State),
+    ) -> Option<(Vec<R::State>, usize)> {
+        self.astar_with_state(
+            &start,
+            |grid, state| {
+                expanded(state);
+                rules.successors(grid, state)
+            },
+            |state| rules.heuristic(self, state),
+            |state| rules.is_goal(self, state),
+        )
+    }
+}
+
+/// A position plus the direction of the last move and how many moves in a row went that way.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct Heading {
+    pub pos: Point<2>,
+    /// None before the first move.
+    pub dir: Option<CardDir>,
+    pub run: usize,
+}
+
+impl Heading {
+    /// A stationary starting state, free to move in any direction.
+    pub fn start(pos: Point<2>) -> Self {
+        Self {
+            pos,
+            dir: None,
+            run: 0,
+        }
+    }
+}
+
+/// Crucible-style movement: at least `min_run` and at most `max_run` moves in a straight line
+/// before turning, and no turning around unless `allow_reverse` is set.  The goal can only be
+/// reached at the end of a run of at least `min_run`.  Each move costs the value of the cell
+/// moved into.
+#[derive(Debug, Copy, Clone)]
+pub struct Crucible {
+    pub min_run: usize,
+    pub max_run: usize,
+    pub allow_reverse: bool,
+    pub end: Point<2>,
+}
+
+impl Rules<usize> for Crucible {
+    type State = Heading;
+
+    fn successors(&self, grid: &Grid<usize>, state: &Heading) -> Vec<(Heading, usize)> {
+        [CardDir::Up, CardDir::Left, CardDir::Right, CardDir::Down]
+            .into_iter()
+            .filter_map(|new_dir| {
+                let run = match state.dir {
+                    None => 1,
+                    Some(dir) if dir == new_dir && state.run < self.max_run => state.run + 1,
+                    Some(dir) if dir == new_dir => return None,
+                    Some(dir) if dir.opposite() == new_dir && !self.allow_reverse => return None,
+                    Some(_) if state.run >= self.min_run => 1,
+                    Some(_) => return None,
+                };
+                let pos = state.pos.move_in_grid(new_dir, grid)?;
+                let cost = grid.get(pos.x(), pos.y())?;
+                Some((
+                    Heading {
+                        pos,
+                        dir: Some(new_dir),
+                        run,
+                    },
+                    cost,
+                ))
+            })
+            .collect()
+    }
+
+    fn heuristic(&self, _grid: &Grid<usize>, state: &Heading) -> usize {
+        state.pos.manhattan(&self.end)
+    }
+
+    fn is_goal(&self, _grid: &Grid<usize>, state: &Heading) -> bool {
+        state.pos == self.end && state.run >= self.min_run
+    }
+}
+
+/// Ice-sliding movement: each move continues in a straight line until the next cell is blocked
+/// or off the grid, and costs the number of cells travelled.
+pub struct IceSlide<F> {
+    /// Whether a cell stops a slide.
+    pub blocked: F,
+    pub end: Point<2>,
+}
+
+impl<T: Copy, F: Fn(T) -> bool> Rules<T> for IceSlide<F> {
+    type State = Point<2>;
+
+    fn successors(&self, grid: &Grid<T>, state: &Point<2>) -> Vec<(Point<2>, usize)> {
+        [CardDir::Up, CardDir::Left, CardDir::Right, CardDir::Down]
+            .into_iter()
+            .filter_map(|dir| {
+                let mut pos = *state;
+                let mut dist = 0;
+                while let Some(next) = pos.move_in_grid(dir, grid) {
+                    if grid
+                        .get(next.x(), next.y())
+                        .is_some_and(|t| (self.blocked)(t))
+                    {
+                        break;
+                    }
+                    pos = next;
+                    dist += 1;
+                }
+                (dist > 0).then_some((pos, dist))
+            })
+            .collect()
+    }
+
+    fn heuristic(&self, _grid: &Grid<T>, state: &Point<2>) -> usize {
+        state.manhattan(&self.end)
+    }
+
+    fn is_goal(&self, _grid: &Grid<T>, state: &Point<2>) -> bool {
+        *state == self.end
+    }
+}
+
+/// One-way slope movement: a slope cell can be stepped onto from any side, but can only be left
+/// in the direction it points.  Each move costs 1.  This finds the shortest route; for a longest
+/// route (d23), `successors` still gives the legal moves for a search that doesn't revisit cells.
+pub struct Slopes<F, G> {
+    /// The direction a cell must be left in, if it's a slope.
+    pub slope: F,
+    /// Whether a cell can't be entered.
+    pub blocked: G,
+    pub end: Point<2>,
+}
+
+impl<T: Copy, F: Fn(T) -> Option<CardDir>, G: Fn(T) -> bool> Rules<T> for Slopes<F, G> {
+    type State = Point<2>;
+
+    fn successors(&self, grid: &Grid<T>, state: &Point<2>) -> Vec<(Point<2>, usize)> {
+        let dirs = match grid.get(state.x(), state.y()).and_then(&self.slope) {
+            Some(dir) => vec![dir],
+            None => vec![CardDir::Up, CardDir::Left, CardDir::Right, CardDir::Down],
+        };
+        dirs.into_iter()
+            .filter_map(|dir| state.move_in_grid(dir, grid))
+            .filter(|next| {
+                grid.get(next.x(), next.y())
+                    .is_some_and(|t| !(self.blocked)(t))
+            })
+            .map(|next| (next, 1))
+            .collect()
+    }
+
+    fn heuristic(&self, _grid: &Grid<T>, state: &Point<2>) -> usize {
+        state.manhattan(&self.end)
+    }
+
+    fn is_goal(&self, _grid: &Grid<T>, state: &Point<2>) -> bool {
+        *state == self.end
+    }
+}
+
+#[cfg(test)]
+mod pathfinder_tests {
+    use super::*;
+
+    #[test]
+    fn crucible_max_run_test() {
+        // the cheap row is too long to take without a detour
+        #[rustfmt::skip]
+        let g = Grid::new(vec![
+            vec![1, 1, 1, 1, 1],
+            vec![5, 5, 5, 5, 1],
+        ]);
+        let rules = |max_run| Crucible {
+            min_run: 1,
+            max_run,
+            allow_reverse: false,
+            end: [4, 1].into(),
+        };
+        let (_, unlimited) = g
+            .find_path(&rules(10), Heading::start([0, 0].into()))
+            .unwrap();
+        let (_, limited) = g
+            .find_path(&rules(3), Heading::start([0, 0].into()))
+            .unwrap();
+        assert_eq!(unlimited, 5);
+        assert!(limited > unlimited);
+    }
+
+    #[test]
+    fn crucible_min_run_test() {
+        let g = Grid::new(vec![vec![1; 3]; 3]);
+        let rules = Crucible {
+            min_run: 3,
+            max_run: 3,
+            allow_reverse: false,
+            end: [2, 2].into(),
+        };
+        // a run of 3 moves can't fit in a 3x3 grid
+        assert_eq!(g.find_path(&rules, Heading::start([0, 0].into())), None);
+    }
+
+    #[test]
+    fn find_path_watched_test() {
+        let g = Grid::new(vec![vec![1; 3]; 3]);
+        let rules = Crucible {
+            min_run: 1,
+            max_run: 3,
+            allow_reverse: false,
+            end: [2, 2].into(),
+        };
+        let mut expanded = vec![];
+        let (path, cost) = g
+            .find_path_watched(&rules, Heading::start([0, 0].into()), |s| {
+                expanded.push(s.pos)
+            })
+            .unwrap();
+        assert_eq!(cost, 4);
+        assert_eq!(path.len(), 5);
+        // the start is expanded first, and the goal is never expanded
+        assert_eq!(expanded[0], [0, 0].into());
+        assert!(!expanded.contains(&[2, 2].into()));
+    }
+
+    #[test]
+    fn slopes_test() {
+        #[rustfmt::skip]
+        let g = Grid::from_rows([
+            "..<..",
+            ".....",
+            "#.#..",
+        ]);
+        let rules = |end| Slopes {
+            slope: |c| CardDir::try_from(c).ok(),
+            blocked: |c| c == '#',
+            end,
+        };
+        // downhill through the slope is direct
+        let (path, cost) = g.find_path(&rules([0, 0].into()), [4, 0].into()).unwrap();
+        assert_eq!(cost, 4);
+        assert!(path.contains(&[2, 0].into()));
+        // uphill has to go around it
+        let (path, cost) = g.find_path(&rules([4, 0].into()), [0, 0].into()).unwrap();
+        assert_eq!(cost, 6);
+        assert!(!path.contains(&[2, 0].into()));
+        // walls can't be entered, and a slope is left the way it points
+        assert_eq!(g.find_path(&rules([0, 2].into()), [1, 2].into()), None);
+        assert_eq!(
+            g.find_path(&rules([1, 0].into()), [2, 0].into()),
+            Some((vec![[2, 0].into(), [1, 0].into()], 1))
+        );
+    }
+
+    #[test]
+    fn ice_slide_test() {
+        #[rustfmt::skip]
+        let g = Grid::from_rows([
+            "...#",
+            "....",
+            "#...",
+        ]);
+        let rules = IceSlide {
+            blocked: |c| c == '#',
+            end: [2, 0].into(),
+        };
+        // up to the edge, then right until the wall stops the slide
+        assert_eq!(
+            g.find_path(&rules, [0, 1].into()),
+            Some((vec![[0, 1].into(), [0, 0].into(), [2, 0].into()], 3))
+        );
+    }
+}
@@ -1,86 +1,419 @@
 use std::{
     fmt::Display,
-    ops::{Add, Sub},
+    ops::{Add, Div, Mul, Neg, Sub},
 };
 
-use pathfinding::num_traits::Zero;
+use pathfinding::num_traits::{
+    CheckedAdd, CheckedSub, NumCast, One, SaturatingAdd, SaturatingSub, Signed, ToPrimitive,
+    WrappingAdd, WrappingSub, Zero,
+};
 
-use crate::{direction::CardDir, grid::Grid};
+use crate::{
+    aabb::Aabb,
+    direction::{CardDir, Dir3, HexDir},
+    grid::Grid,
+};
 
+/// A point in D dimensions.  The scalar type T defaults to usize, which is what grid indexing
+/// uses, so `Point<2>` is a grid position.  Use a signed or floating point T (eg, `Point<2, i64>`)
+/// for coordinates that can go negative.
 #[derive(Eq, PartialEq, Debug, Copy, Clone, Hash, Ord, PartialOrd)]
-pub struct Point<const D: usize> {
-    pub coords: [usize; D],
+pub struct Point<const D: usize, T = usize> {
+    pub coords: [T; D],
 }
 
-impl<const D: usize> Sub for Point<D> {
-    type Output = Point<D>;
+/// A point with signed coordinates.
+pub type IPoint<const D: usize> = Point<D, i64>;
+
+impl<const D: usize, T: Copy + Sub<Output = T>> Sub for Point<D, T> {
+    type Output = Point<D, T>;
 
     fn sub(self, rhs: Self) -> Self::Output {
         let mut new_coords = self.coords;
         for (i, c) in new_coords.iter_mut().enumerate() {
-            *c -= rhs.coords[i];
+            *c = *c - rhs.coords[i];
         }
         new_coords.into()
     }
 }
 
-impl<const D: usize> Add for Point<D> {
-    type Output = Point<D>;
+impl<const D: usize, T: Copy + Add<Output = T>> Add for Point<D, T> {
+    type Output = Point<D, T>;
 
     fn add(self, rhs: Self) -> Self::Output {
         let mut new_coords = self.coords;
         for (i, c) in new_coords.iter_mut().enumerate() {
-            *c += rhs.coords[i];
+            *c = *c + rhs.coords[i];
         }
         new_coords.into()
     }
 }
 
-impl<const D: usize> Zero for Point<D> {
+/// Scale every coordinate by the same amount.
+impl<const D: usize, T: Copy + Mul<Output = T>> Mul<T> for Point<D, T> {
+    type Output = Point<D, T>;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        self.coords.map(|c| c * rhs).into()
+    }
+}
+
+/// Divide every coordinate by the same amount.
+impl<const D: usize, T: Copy + Div<Output = T>> Div<T> for Point<D, T> {
+    type Output = Point<D, T>;
+
+    fn div(self, rhs: T) -> Self::Output {
+        self.coords.map(|c| c / rhs).into()
+    }
+}
+
+impl<const D: usize, T: Copy + Neg<Output = T>> Neg for Point<D, T> {
+    type Output = Point<D, T>;
+
+    fn neg(self) -> Self::Output {
+        self.coords.map(|c| -c).into()
+    }
+}
+
+impl<const D: usize, T: Copy + Zero> Zero for Point<D, T> {
     fn zero() -> Self {
-        [0; D].into()
+        [T::zero(); D].into()
     }
 
     fn is_zero(&self) -> bool {
-        self.coords.iter().all(|c| *c == 0)
+        self.coords.iter().all(|c| c.is_zero())
     }
 }
 
-impl<const D: usize> Point<D> {
-    pub fn new(coords: &[usize; D]) -> Self {
+impl<const D: usize, T: Copy> Point<D, T> {
+    pub fn new(coords: &[T; D]) -> Self {
         Self { coords: *coords }
     }
 
-    pub fn x(&self) -> usize {
+    pub fn x(&self) -> T {
         self.coords[0]
     }
-    pub fn y(&self) -> usize {
+    pub fn y(&self) -> T {
         self.coords[1]
     }
-    pub fn z(&self) -> usize {
+    pub fn z(&self) -> T {
         self.coords[2]
     }
 
     /// Set a new value for the x coordinate.
-    pub fn set_x(&mut self, new_x: usize) {
+    pub fn set_x(&mut self, new_x: T) {
         self.coords[0] = new_x;
     }
 
     /// Set a new value for the y coordinate.
-    pub fn set_y(&mut self, new_y: usize) {
+    pub fn set_y(&mut self, new_y: T) {
         self.coords[1] = new_y;
     }
 
     /// Set a new value for the z coordinate.
-    pub fn set_z(&mut self, new_z: usize) {
+    pub fn set_z(&mut self, new_z: T) {
         self.coords[2] = new_z;
     }
 
-    /// Get the magnitude of the point, considered as a vector.
-    pub fn mag(&self) -> usize {
-        self.coords.iter().product::<usize>() / self.coords.len()
+    /// Combine each coordinate with the matching coordinate of another point, stopping at the
+    /// first None.
+    fn try_zip(&self, rhs: &Self, f: impl Fn(&T, &T) -> Option<T>) -> Option<Self> {
+        let mut new_coords = self.coords;
+        for (i, c) in new_coords.iter_mut().enumerate() {
+            *c = f(c, &rhs.coords[i])?;
+        }
+        Some(new_coords.into())
+    }
+
+    fn zip(&self, rhs: &Self, f: impl Fn(&T, &T) -> T) -> Self {
+        self.try_zip(rhs, |a, b| Some(f(a, b))).unwrap()
+    }
+
+    /// Add two points, or None if any coordinate overflows.
+    pub fn checked_add(&self, rhs: &Self) -> Option<Self>
+    where
+        T: CheckedAdd,
+    {
+        self.try_zip(rhs, T::checked_add)
+    }
+
+    /// Subtract two points, or None if any coordinate overflows (eg, goes below zero for usize).
+    pub fn checked_sub(&self, rhs: &Self) -> Option<Self>
+    where
+        T: CheckedSub,
+    {
+        self.try_zip(rhs, T::checked_sub)
+    }
+
+    /// Add two points, wrapping around at the bounds of the scalar type.
+    pub fn wrapping_add(&self, rhs: &Self) -> Self
+    where
+        T: WrappingAdd,
+    {
+        self.zip(rhs, T::wrapping_add)
+    }
+
+    /// Subtract two points, wrapping around at the bounds of the scalar type.
+    pub fn wrapping_sub(&self, rhs: &Self) -> Self
+    where
+        T: WrappingSub,
+    {
+        self.zip(rhs, T::wrapping_sub)
+    }
+
+    /// Add two points, clamping at the bounds of the scalar type.
+    pub fn saturating_add(&self, rhs: &Self) -> Self
+    where
+        T: SaturatingAdd,
+    {
+        self.zip(rhs, T::saturating_add)
+    }
+
+    /// Subtract two points, clamping at the bounds of the scalar type.
+    pub fn saturating_sub(&self, rhs: &Self) -> Self
+    where
+        T: SaturatingSub,
+    {
+        self.zip(rhs, T::saturating_sub)
+    }
+
+    /// The dot product of two points, considered as vectors.
+    pub fn dot(&self, rhs: &Self) -> T
+    where
+        T: Zero + Mul<Output = T>,
+    {
+        (0..D).fold(T::zero(), |sum, i| sum + self.coords[i] * rhs.coords[i])
+    }
+
+    /// The distance between each pair of coordinates.  Works for unsigned types too.
+    fn abs_diffs(&self, rhs: &Self) -> [T; D]
+    where
+        T: PartialOrd + Sub<Output = T>,
+    {
+        let mut diffs = self.coords;
+        for (i, d) in diffs.iter_mut().enumerate() {
+            let (a, b) = (self.coords[i], rhs.coords[i]);
+            *d = if a > b { a - b } else { b - a };
+        }
+        diffs
+    }
+
+    /// The taxicab distance: the sum of the distances along each axis.
+    pub fn manhattan(&self, rhs: &Self) -> T
+    where
+        T: PartialOrd + Zero + Sub<Output = T>,
+    {
+        self.abs_diffs(rhs)
+            .into_iter()
+            .fold(T::zero(), |sum, d| sum + d)
+    }
+
+    /// The chessboard distance: the largest distance along any one axis.
+    pub fn chebyshev(&self, rhs: &Self) -> T
+    where
+        T: PartialOrd + Zero + Sub<Output = T>,
+    {
+        self.abs_diffs(rhs)
+            .into_iter()
+            .fold(T::zero(), |max, d| if d > max { d } else { max })
+    }
+
+    /// The straight line distance between two points.
+    pub fn euclidean(&self, rhs: &Self) -> f64
+    where
+        T: PartialOrd + Sub<Output = T> + ToPrimitive,
+    {
+        self.abs_diffs(rhs)
+            .iter()
+            .map(|d| d.to_f64().unwrap().powi(2))
+            .sum::<f64>()
+            .sqrt()
+    }
+
+    /// The length of the point, considered as a vector from the origin.
+    pub fn mag(&self) -> f64
+    where
+        T: ToPrimitive,
+    {
+        self.coords
+            .iter()
+            .map(|c| c.to_f64().unwrap().powi(2))
+            .sum::<f64>()
+            .sqrt()
+    }
+
+    /// The smaller of each pair of coordinates.
+    pub fn component_min(&self, rhs: &Self) -> Self
+    where
+        T: PartialOrd,
+    {
+        self.zip(rhs, |a, b| if b < a { *b } else { *a })
+    }
+
+    /// The larger of each pair of coordinates.
+    pub fn component_max(&self, rhs: &Self) -> Self
+    where
+        T: PartialOrd,
+    {
+        self.zip(rhs, |a, b| if b > a { *b } else { *a })
+    }
+
+    /// -1, 0 or 1 in each coordinate, matching its sign.  Turns a vector into a single step in
+    /// (roughly) the same direction.
+    pub fn signum(&self) -> Self
+    where
+        T: Signed,
+    {
+        self.coords.map(|c| c.signum()).into()
+    }
+
+    /// The absolute value of each coordinate.
+    pub fn abs(&self) -> Self
+    where
+        T: Signed,
+    {
+        self.coords.map(|c| c.abs()).into()
+    }
+
+    /// Convert to another scalar type, or None if any coordinate can't be represented in it (eg,
+    /// a negative coordinate converted to usize).  Floats are truncated when converted to
+    /// integers.
+    pub fn cast<U: NumCast + Copy>(&self) -> Option<Point<D, U>>
+    where
+        T: ToPrimitive,
+    {
+        let converted = self.coords.map(U::from);
+        if converted.iter().any(Option::is_none) {
+            return None;
+        }
+        Some(converted.map(Option::unwrap).into())
+    }
+}
+
+impl<T: Copy + Sub<Output = T> + Mul<Output = T>> Point<3, T> {
+    /// The cross product of two vectors, which is perpendicular to both.
+    pub fn cross(&self, rhs: &Self) -> Self {
+        let [ax, ay, az] = self.coords;
+        let [bx, by, bz] = rhs.coords;
+        [ay * bz - az * by, az * bx - ax * bz, ax * by - ay * bx].into()
+    }
+}
+
+/// Rotations use grid orientation, where y increases downwards, so turning right takes Up
+/// (0, -1) to Right (1, 0).
+impl<T: Copy + Neg<Output = T>> Point<2, T> {
+    /// Rotate a quarter turn clockwise around the origin.
+    pub fn rotate_right(&self) -> Self {
+        [-self.y(), self.x()].into()
+    }
+
+    /// Rotate a quarter turn anticlockwise around the origin.
+    pub fn rotate_left(&self) -> Self {
+        [self.y(), -self.x()].into()
+    }
+
+    /// Rotate a half turn around the origin.
+    pub fn rotate_180(&self) -> Self {
+        [-self.x(), -self.y()].into()
+    }
+}
+
+/// Which points count as neighbours of a point.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Neighborhood {
+    /// Points one step away along a single axis: 4 in 2D, 6 in 3D, 2D in general.
+    VonNeumann,
+    /// Points at most one step away along every axis, including diagonals: 8 in 2D, 26 in 3D,
+    /// 3^D - 1 in general.
+    Moore,
+}
+
+impl<const D: usize, T: Copy + One + CheckedAdd + CheckedSub> Point<D, T> {
+    /// The neighbours of this point, in reading order (the first axis changes fastest, so in 2D
+    /// the von Neumann neighbours are up, left, right, down).  Neighbours whose coordinates
+    /// would overflow the scalar type, eg. below zero for usize, are skipped.
+    pub fn neighbors(&self, kind: Neighborhood) -> impl Iterator<Item = Self> {
+        let p = *self;
+        // each base 3 digit of n is the offset along one axis: 0 is -1, 1 is 0 and 2 is +1
+        (0..3usize.pow(D as u32)).filter_map(move |n| {
+            let digits: [usize; D] = std::array::from_fn(|axis| n / 3usize.pow(axis as u32) % 3);
+            let moved_axes = digits.iter().filter(|&&d| d != 1).count();
+            let wanted = match kind {
+                Neighborhood::VonNeumann => moved_axes == 1,
+                Neighborhood::Moore => moved_axes > 0,
+            };
+            if !wanted {
+                return None;
+            }
+            let mut coords = p.coords;
+            for (c, d) in coords.iter_mut().zip(digits) {
+                *c = match d {
+                    0 => c.checked_sub(&T::one())?,
+                    2 => c.checked_add(&T::one())?,
+                    _ => *c,
+                };
+            }
+            Some(coords.into())
+        })
+    }
+
+    /// The neighbours of this point which lie inside `bounds`.
+    pub fn neighbors_within<'a>(
+        &self,
+        kind: Neighborhood,
+        bounds: &'a Aabb<D, T>,
+    ) -> impl Iterator<Item = Self> + 'a
+    where
+        T: PartialOrd,
+    {
+        self.neighbors(kind).filter(|p| bounds.contains(p))
+    }
+
+    /// The neighbours of this point for which `keep` returns true.
+    pub fn neighbors_where(
+        &self,
+        kind: Neighborhood,
+        keep: impl Fn(&Self) -> bool,
+    ) -> impl Iterator<Item = Self> {
+        self.neighbors(kind).filter(move |p| keep(p))
+    }
+}
+
+/// Hexagonal grids, in axial (q, r) coordinates stored as (x, y).
+impl Point<2, i64> {
+    /// The neighbouring hexagon in the given direction.  Works with either hexagon orientation.
+    pub fn hex_step(&self, dir: impl HexDir) -> Self {
+        let (dq, dr) = dir.delta();
+        [self.x() + dq, self.y() + dr].into()
+    }
+
+    /// All six neighbouring hexagons, in the order of H::ALL.
+    pub fn hex_neighbors<H: HexDir>(&self) -> [Self; 6] {
+        H::ALL.map(|dir| self.hex_step(dir))
     }
 
+    /// The fewest steps between two hexagons.
+    pub fn hex_distance(&self, rhs: &Self) -> i64 {
+        let (dq, dr) = (self.x() - rhs.x(), self.y() - rhs.y());
+        (dq.abs() + dr.abs() + (dq + dr).abs()) / 2
+    }
+}
+
+impl<T: Copy + One + CheckedAdd + CheckedSub> Point<3, T> {
+    /// The neighbouring point in the given direction, or None if a coordinate would overflow
+    /// (eg, below zero for usize).
+    pub fn step(&self, dir: Dir3) -> Option<Self> {
+        let mut p = *self;
+        let c = &mut p.coords[dir.axis()];
+        *c = match dir {
+            Dir3::East | Dir3::South | Dir3::Up => c.checked_add(&T::one())?,
+            Dir3::West | Dir3::North | Dir3::Down => c.checked_sub(&T::one())?,
+        };
+        Some(p)
+    }
+}
+
+impl<const D: usize> Point<D> {
     /// Attempt to move the point one unit in the given direction, within a grid bounds.  Returns
     /// None if the move would push the point outside the bounds of the grid.
     pub fn move_in_grid<T: Copy>(&self, dir: CardDir, grid: &Grid<T>) -> Option<Point<D>> {
@@ -102,20 +435,20 @@ impl<const D: usize> Point<D> {
 }
 
 // Make possible the nice pattern `&[1,2,3].into()` to create a Point.
-impl<const D: usize> From<&[usize; D]> for Point<D> {
-    fn from(coords: &[usize; D]) -> Self {
+impl<const D: usize, T: Copy> From<&[T; D]> for Point<D, T> {
+    fn from(coords: &[T; D]) -> Self {
         Point { coords: *coords }
     }
 }
 
 // Make possible the nice pattern `[1,2,3].into()` to create a Point.
-impl<const D: usize> From<[usize; D]> for Point<D> {
-    fn from(coords: [usize; D]) -> Self {
+impl<const D: usize, T> From<[T; D]> for Point<D, T> {
+    fn from(coords: [T; D]) -> Self {
         Point { coords }
     }
 }
 
-impl<const D: usize> Display for Point<D> {
+impl<const D: usize, T: Display> Display for Point<D, T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "(")?;
         for (i, n) in self.coords.iter().enumerate() {
@@ -127,3 +460,160 @@ impl<const D: usize> Display for Point<D> {
         write!(f, ")")
     }
 }
+
+#[cfg(test)]
+mod point_tests {
+    use super::*;
+
+    #[test]
+    fn signed_point_test() {
+        let a: IPoint<2> = [1, 2].into();
+        let b: IPoint<2> = [3, -4].into();
+        assert_eq!(a - b, [-2, 6].into());
+        assert_eq!(a + b, [4, -2].into());
+        assert_eq!((a - b).to_string(), "(-2, 6)");
+    }
+
+    #[test]
+    fn checked_test() {
+        let a: Point<2> = [1, 2].into();
+        let b: Point<2> = [2, 1].into();
+        assert_eq!(a.checked_sub(&b), None);
+        assert_eq!(a.checked_add(&b), Some([3, 3].into()));
+        assert_eq!(a.saturating_sub(&b), [0, 1].into());
+        assert_eq!(a.wrapping_sub(&b), [usize::MAX, 1].into());
+
+        let big: Point<2, i32> = [i32::MAX, 0].into();
+        let one: Point<2, i32> = [1, 1].into();
+        assert_eq!(big.checked_add(&one), None);
+        assert_eq!(big.saturating_add(&one), [i32::MAX, 1].into());
+        assert_eq!(big.wrapping_add(&one), [i32::MIN, 1].into());
+    }
+
+    #[test]
+    fn scalar_test() {
+        let p: IPoint<2> = [3, -4].into();
+        assert_eq!(p * 2, [6, -8].into());
+        assert_eq!(p / 2, [1, -2].into());
+        assert_eq!(-p, [-3, 4].into());
+        assert_eq!(p.abs(), [3, 4].into());
+        assert_eq!(p.signum(), [1, -1].into());
+        assert_eq!(p.mag(), 5.0);
+    }
+
+    #[test]
+    fn products_test() {
+        let a: IPoint<3> = [1, 0, 0].into();
+        let b: IPoint<3> = [0, 1, 0].into();
+        assert_eq!(a.dot(&b), 0);
+        assert_eq!(a.cross(&b), [0, 0, 1].into());
+        assert_eq!(b.cross(&a), [0, 0, -1].into());
+        let c: Point<3> = [1, 2, 3].into();
+        assert_eq!(c.dot(&c), 14);
+    }
+
+    #[test]
+    fn distance_test() {
+        let a: Point<2> = [1, 5].into();
+        let b: Point<2> = [4, 1].into();
+        assert_eq!(a.manhattan(&b), 7);
+        assert_eq!(b.manhattan(&a), 7);
+        assert_eq!(a.chebyshev(&b), 4);
+        assert_eq!(a.euclidean(&b), 5.0);
+        assert_eq!(a.component_min(&b), [1, 1].into());
+        assert_eq!(a.component_max(&b), [4, 5].into());
+    }
+
+    #[test]
+    fn rotate_test() {
+        let up: IPoint<2> = [0, -1].into();
+        assert_eq!(up.rotate_right(), [1, 0].into());
+        assert_eq!(up.rotate_left(), [-1, 0].into());
+        assert_eq!(up.rotate_180(), [0, 1].into());
+        assert_eq!(
+            up.rotate_right().rotate_right().rotate_right(),
+            up.rotate_left()
+        );
+    }
+
+    #[test]
+    fn neighbors_test() {
+        let p: Point<2> = [1, 1].into();
+        let adj_4: Vec<_> = p.neighbors(Neighborhood::VonNeumann).collect();
+        assert_eq!(
+            adj_4,
+            vec![[1, 0].into(), [0, 1].into(), [2, 1].into(), [1, 2].into()]
+        );
+        assert_eq!(p.neighbors(Neighborhood::Moore).count(), 8);
+
+        // usize coordinates can't go below zero
+        let corner: Point<2> = [0, 0].into();
+        assert_eq!(corner.neighbors(Neighborhood::Moore).count(), 3);
+
+        let origin: IPoint<3> = [0, 0, 0].into();
+        assert_eq!(origin.neighbors(Neighborhood::VonNeumann).count(), 6);
+        assert_eq!(origin.neighbors(Neighborhood::Moore).count(), 26);
+        let origin: IPoint<4> = [0, 0, 0, 0].into();
+        assert_eq!(origin.neighbors(Neighborhood::VonNeumann).count(), 8);
+        assert_eq!(origin.neighbors(Neighborhood::Moore).count(), 80);
+    }
+
+    #[test]
+    fn neighbors_bounded_test() {
+        let p: Point<3> = [2, 0, 5].into();
+        let bounds = Aabb::new([0, 0, 0].into(), [2, 2, 9].into());
+        let within: Vec<_> = p
+            .neighbors_within(Neighborhood::VonNeumann, &bounds)
+            .collect();
+        assert_eq!(
+            within,
+            vec![
+                [2, 0, 4].into(),
+                [1, 0, 5].into(),
+                [2, 1, 5].into(),
+                [2, 0, 6].into()
+            ]
+        );
+        let above = p.neighbors_where(Neighborhood::Moore, |q| q.z() > p.z());
+        assert_eq!(above.count(), 6);
+    }
+
+    #[test]
+    fn hex_test() {
+        use crate::direction::{FlatHexDir, PointyHexDir};
+
+        let origin: IPoint<2> = [0, 0].into();
+        let p = origin
+            .hex_step(PointyHexDir::E)
+            .hex_step(PointyHexDir::E)
+            .hex_step(PointyHexDir::SE);
+        assert_eq!(p, [2, 1].into());
+        assert_eq!(origin.hex_distance(&p), 3);
+        assert_eq!(origin.hex_step(FlatHexDir::NE), [1, -1].into());
+        for n in origin.hex_neighbors::<FlatHexDir>() {
+            assert_eq!(origin.hex_distance(&n), 1);
+        }
+    }
+
+    #[test]
+    fn step_3d_test() {
+        let p: Point<3> = [0, 0, 1].into();
+        assert_eq!(p.step(Dir3::Down), Some([0, 0, 0].into()));
+        assert_eq!(p.step(Dir3::West), None);
+        assert_eq!(p.step(Dir3::South), Some([0, 1, 1].into()));
+    }
+
+    #[test]
+    fn cast_test() {
+        let p: IPoint<3> = [1, -2, 3].into();
+        assert_eq!(p.cast::<usize>(), None);
+        assert_eq!(p.cast::<i32>(), Some([1, -2, 3].into()));
+        assert_eq!(p.cast::<f64>(), Some([1.0, -2.0, 3.0].into()));
+        let f: Point<2, f64> = [1.5, 2.9].into();
+        assert_eq!(f.cast::<usize>(), Some([1, 2].into()));
+        let i: Point<2, i128> = [i128::MAX, 0].into();
+        assert_eq!(i.cast::<i64>(), None);
+        let empty: Point<0, i64> = [].into();
+        assert_eq!(empty.cast::<u8>(), Some([].into()));
+    }
+}
@@ -0,0 +1,195 @@
+//! Line-of-sight queries: walk across a Grid in a straight line from a starting cell.
+
+use crate::{
+    direction::OrdDir,
+    grid::{Cell, Grid},
+    point::Point,
+};
+
+/// One cell visited by a ray, and how many steps it took to get there.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct RayStep<T> {
+    pub cell: Cell<T>,
+    pub dist: usize,
+}
+
+/// An iterator over the cells in a straight line from (but not including) a starting point.
+/// Produced by Grid::ray.
+#[derive(Debug, Clone)]
+pub struct Ray<'a, T: Copy> {
+    grid: &'a Grid<T>,
+    start: Point<2>,
+    pos: Point<2>,
+    delta: (i64, i64),
+    dist: usize,
+    wrap: bool,
+    done: bool,
+}
+
+impl<'a, T: Copy> Ray<'a, T> {
+    /// Wrap around to the opposite edge instead of stopping at the edge of the grid.  A wrapping
+    /// ray stops when it gets back to its starting point.
+    pub fn wrapping(mut self) -> Self {
+        self.wrap = true;
+        self
+    }
+
+    /// Stop after the first cell for which `stop` returns true, including that cell.
+    pub fn until<F>(self, mut stop: F) -> impl Iterator<Item = RayStep<T>> + 'a
+    where
+        F: FnMut(T) -> bool + 'a,
+    {
+        let mut stopped = false;
+        self.take_while(move |step| {
+            if stopped {
+                return false;
+            }
+            stopped = stop(step.cell.data);
+            true
+        })
+    }
+}
+
+impl<T: Copy> Iterator for Ray<'_, T> {
+    type Item = RayStep<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let (w, h) = (self.grid.width() as i64, self.grid.height() as i64);
+        let mut x = self.pos.x() as i64 + self.delta.0;
+        let mut y = self.pos.y() as i64 + self.delta.1;
+        if self.wrap {
+            x = x.rem_euclid(w);
+            y = y.rem_euclid(h);
+        }
+
+        if !(0..w).contains(&x) || !(0..h).contains(&y) || self.delta == (0, 0) {
+            self.done = true;
+            return None;
+        }
+
+        let pos: Point<2> = [x as usize, y as usize].into();
+        if pos == self.start {
+            self.done = true;
+            return None;
+        }
+
+        self.pos = pos;
+        self.dist += 1;
+        Some(RayStep {
+            cell: Cell::new(pos, self.grid.cells[pos.y()][pos.x()]),
+            dist: self.dist,
+        })
+    }
+}
+
+impl<T: Copy> Grid<T> {
+    /// Walk from `start` in a straight line, in any cardinal or ordinal direction, yielding each
+    /// cell passed through until the edge of the grid.  The start cell isn't included.  A ray
+    /// starting outside the grid is empty, even when wrapping, as it could never get back to its
+    /// start.
+    pub fn ray(&self, start: Point<2>, dir: impl Into<OrdDir>) -> Ray<'_, T> {
+        Ray {
+            grid: self,
+            start,
+            pos: start,
+            delta: dir.into().delta(),
+            dist: 0,
+            wrap: false,
+            done: start.x() >= self.width() || start.y() >= self.height(),
+        }
+    }
+
+    /// Find the first cell visible from `start` in the given direction for which `hit` returns
+    /// true, along with its distance from the start.
+    pub fn cast(
+        &self,
+        start: Point<2>,
+        dir: impl Into<OrdDir>,
+        hit: impl Fn(T) -> bool,
+    ) -> Option<RayStep<T>> {
+        self.ray(start, dir).find(|step| hit(step.cell.data))
+    }
+}
+
+#[cfg(test)]
+mod ray_tests {
+    use super::*;
+    use crate::direction::CardDir;
+
+    fn grid() -> Grid<char> {
+        #[rustfmt::skip]
+        let rows = [
+            "a.#.",
+            "....",
+            "..b.",
+        ];
+        Grid::from_rows(rows)
+    }
+
+    #[test]
+    fn ray_test() {
+        let g = grid();
+        let cells: Vec<_> = g
+            .ray([0, 0].into(), CardDir::Right)
+            .map(|step| (step.cell.data, step.dist))
+            .collect();
+        assert_eq!(cells, vec![('.', 1), ('#', 2), ('.', 3)]);
+        assert_eq!(g.ray([0, 0].into(), OrdDir::UpLeft).count(), 0);
+    }
+
+    #[test]
+    fn ray_diagonal_test() {
+        let g = grid();
+        let last = g.ray([0, 0].into(), OrdDir::DownRight).last().unwrap();
+        assert_eq!(last.cell, Cell::new([2, 2].into(), 'b'));
+        assert_eq!(last.dist, 2);
+    }
+
+    #[test]
+    fn ray_wrapping_test() {
+        let g = grid();
+        let cells: String = g
+            .ray([1, 0].into(), CardDir::Left)
+            .wrapping()
+            .map(|step| step.cell.data)
+            .collect();
+        assert_eq!(cells, "a.#");
+    }
+
+    #[test]
+    fn ray_outside_grid_test() {
+        let g = grid();
+        assert_eq!(g.ray([4, 0].into(), CardDir::Left).count(), 0);
+        assert_eq!(g.ray([0, 7].into(), CardDir::Up).wrapping().count(), 0);
+
+        let empty: Grid<char> = Grid::new(vec![]);
+        assert_eq!(
+            empty.ray([0, 0].into(), CardDir::Right).wrapping().count(),
+            0
+        );
+    }
+
+    #[test]
+    fn ray_until_test() {
+        let g = grid();
+        let cells: String = g
+            .ray([3, 0].into(), CardDir::Left)
+            .until(|c| c == '#')
+            .map(|step| step.cell.data)
+            .collect();
+        assert_eq!(cells, "#");
+    }
+
+    #[test]
+    fn cast_test() {
+        let g = grid();
+        let hit = g.cast([2, 0].into(), CardDir::Down, |c| c != '.').unwrap();
+        assert_eq!(hit.cell.data, 'b');
+        assert_eq!(hit.dist, 2);
+        assert_eq!(g.cast([0, 1].into(), CardDir::Right, |c| c != '.'), None);
+    }
+}
@@ -0,0 +1,465 @@
+//! Save visualizations to files: asciinema casts, animated GIFs and numbered PNGs.
+//!
+//! Each recorder is a Visualizer, so a solution records the same frames it would show live.
+//! Frames are timed by the recording's frame rate rather than the wall clock, so a slow
+//! solution still plays back smoothly.
+
+use std::{
+    fmt::Write as _,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use crate::{
+    render::Image,
+    visualize::{Control, Frame, Visualizer},
+};
+
+/// Which frames to keep, and how fast to play them back.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct RecordOptions {
+    /// Playback speed, in frames per second.
+    pub fps: u32,
+    /// Keep only every nth frame.  1 keeps them all.
+    pub every: usize,
+    /// Stop recording once the recording is this long.
+    pub max_duration: Option<Duration>,
+    /// Width and height of each cell, in pixels, for image formats.
+    pub cell_size: usize,
+}
+
+impl Default for RecordOptions {
+    fn default() -> Self {
+        Self {
+            fps: 30,
+            every: 1,
+            max_duration: None,
+            cell_size: 8,
+        }
+    }
+}
+
+/// Applies RecordOptions to a stream of frames.
+#[derive(Debug, Clone)]
+struct Clock {
+    options: RecordOptions,
+    /// Frames offered, including skipped ones.
+    seen: usize,
+    /// Frames recorded.
+    kept: usize,
+    done: bool,
+}
+
+impl Clock {
+    fn new(options: RecordOptions) -> Self {
+        assert!(options.fps > 0, "fps must be positive");
+        assert!(options.every > 0, "every must be positive");
+        Self {
+            options,
+            seen: 0,
+            kept: 0,
+            done: false,
+        }
+    }
+
+    /// The time of the next frame within the recording, or None if it should be skipped.
+    fn tick(&mut self) -> Option<Duration> {
+        if self.done {
+            return None;
+        }
+        let index = self.seen;
+        self.seen += 1;
+        if !index.is_multiple_of(self.options.every) {
+            return None;
+        }
+        let at = Duration::from_secs(self.kept as u64) / self.options.fps;
+        if self.options.max_duration.is_some_and(|max| at > max) {
+            self.done = true;
+            return None;
+        }
+        self.kept += 1;
+        Some(at)
+    }
+
+    /// What to tell the solution after a frame.
+    fn control(&self) -> Control {
+        if self.done {
+            Control::Quit
+        } else {
+            Control::Continue
+        }
+    }
+}
+
+/// Draw a frame as an image.  Each cell is filled with its background colour, and any character
+/// other than a space is drawn as a block of its foreground colour in the middle of the cell.
+pub fn frame_image(frame: &Frame, cell_size: usize) -> Image {
+    let size = cell_size as i64;
+    let dot = (size / 2).max(1);
+    let mut image = Image::new(frame.width * cell_size, frame.height * cell_size);
+    for y in 0..frame.height {
+        for x in 0..frame.width {
+            let styled = frame.get(x, y).unwrap();
+            let (px, py) = (x as i64 * size, y as i64 * size);
+            image.fill_rect(px, py, size, size, styled.bg);
+            if styled.ch != ' ' {
+                let inset = (size - dot) / 2;
+                image.fill_rect(px + inset, py + inset, dot, dot, styled.fg);
+            }
+        }
+    }
+    image
+}
+
+/// Escape text for a JSON string.
+fn json_string(text: &str) -> String {
+    let mut out = String::from('"');
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// The terminal output which draws a frame from the top left corner, in 24-bit colour.
+fn ansi(frame: &Frame) -> String {
+    let mut out = String::from("\x1b[H\x1b[2J");
+    for y in 0..frame.height {
+        let mut current = None;
+        for x in 0..frame.width {
+            let styled = frame.get(x, y).unwrap();
+            if current != Some((styled.fg, styled.bg)) {
+                let ([fr, fg, fb], [br, bg, bb]) = (styled.fg, styled.bg);
+                write!(out, "\x1b[38;2;{fr};{fg};{fb};48;2;{br};{bg};{bb}m").unwrap();
+                current = Some((styled.fg, styled.bg));
+            }
+            out.push(styled.ch);
+        }
+        out.push_str("\x1b[0m\r\n");
+    }
+    out.push_str(&frame.status);
+    out
+}
+
+/// Records frames as an asciicast v2 file, which asciinema can play back in a terminal.
+pub struct CastRecorder<W: Write> {
+    out: W,
+    clock: Clock,
+    wrote_header: bool,
+}
+
+impl CastRecorder<BufWriter<File>> {
+    pub fn create(path: impl AsRef<Path>, options: RecordOptions) -> io::Result<Self> {
+        Ok(Self::new(BufWriter::new(File::create(path)?), options))
+    }
+}
+
+impl<W: Write> CastRecorder<W> {
+    pub fn new(out: W, options: RecordOptions) -> Self {
+        Self {
+            out,
+            clock: Clock::new(options),
+            wrote_header: false,
+        }
+    }
+
+    /// Finish writing, and hand back the writer.
+    pub fn into_inner(mut self) -> W {
+        self.out.flush().expect("couldn't write recording");
+        self.out
+    }
+
+    fn write_frame(&mut self, at: Duration, frame: &Frame) -> io::Result<()> {
+        if !self.wrote_header {
+            writeln!(
+                self.out,
+                r#"{{"version": 2, "width": {}, "height": {}}}"#,
+                frame.width.max(frame.status.chars().count()),
+                frame.height + 1
+            )?;
+            self.wrote_header = true;
+        }
+        writeln!(
+            self.out,
+            r#"[{:.6}, "o", {}]"#,
+            at.as_secs_f64(),
+            json_string(&ansi(frame))
+        )
+    }
+}
+
+impl<W: Write> Visualizer for CastRecorder<W> {
+    fn is_enabled(&self) -> bool {
+        !self.clock.done
+    }
+
+    fn show(&mut self, frame: &Frame) -> Control {
+        if let Some(at) = self.clock.tick() {
+            self.write_frame(at, frame)
+                .expect("couldn't write recording");
+        }
+        self.clock.control()
+    }
+}
+
+/// Saves each frame as a numbered PNG in a directory: frame_00000.png, frame_00001.png, ...
+pub struct PngSequence {
+    dir: PathBuf,
+    clock: Clock,
+}
+
+impl PngSequence {
+    /// Start a sequence in `dir`, creating it if needed.
+    pub fn create(dir: impl AsRef<Path>, options: RecordOptions) -> io::Result<Self> {
+        std::fs::create_dir_all(&dir)?;
+        Ok(Self {
+            dir: dir.as_ref().to_path_buf(),
+            clock: Clock::new(options),
+        })
+    }
+}
+
+impl Visualizer for PngSequence {
+    fn is_enabled(&self) -> bool {
+        !self.clock.done
+    }
+
+    fn show(&mut self, frame: &Frame) -> Control {
+        if self.clock.tick().is_some() {
+            let path = self
+                .dir
+                .join(format!("frame_{:05}.png", self.clock.kept - 1));
+            frame_image(frame, self.clock.options.cell_size)
+                .save_png(path)
+                .expect("couldn't write recording");
+        }
+        self.clock.control()
+    }
+}
+
+/// Records frames as a looping animated GIF.
+#[cfg(feature = "visualize")]
+pub struct GifRecorder<W: Write> {
+    /// Taken when the encoder starts, on the first frame.
+    out: Option<W>,
+    encoder: Option<gif::Encoder<W>>,
+    /// Every frame's width and height in pixels, set from the first frame.
+    size: (u16, u16),
+    clock: Clock,
+}
+
+#[cfg(feature = "visualize")]
+impl GifRecorder<BufWriter<File>> {
+    pub fn create(path: impl AsRef<Path>, options: RecordOptions) -> io::Result<Self> {
+        Ok(Self::new(BufWriter::new(File::create(path)?), options))
+    }
+}
+
+#[cfg(feature = "visualize")]
+impl<W: Write> GifRecorder<W> {
+    pub fn new(out: W, options: RecordOptions) -> Self {
+        Self {
+            out: Some(out),
+            encoder: None,
+            size: (0, 0),
+            clock: Clock::new(options),
+        }
+    }
+
+    /// Finish writing, and hand back the writer.
+    pub fn into_inner(self) -> W {
+        match self.encoder {
+            Some(encoder) => encoder.into_inner().expect("couldn't write recording"),
+            None => self.out.unwrap(),
+        }
+    }
+
+    /// Fails if the frame is too big for a GIF, or isn't the size of the first frame.
+    fn write_frame(&mut self, frame: &Frame) -> io::Result<()> {
+        let image = frame_image(frame, self.clock.options.cell_size);
+        let (Ok(width), Ok(height)) = (u16::try_from(image.width), u16::try_from(image.height))
+        else {
+            return Err(io::Error::other(format!(
+                "a {}x{} frame is too big for a GIF",
+                image.width, image.height
+            )));
+        };
+        let encoder = match &mut self.encoder {
+            Some(encoder) => encoder,
+            None => {
+                let mut encoder = gif::Encoder::new(self.out.take().unwrap(), width, height, &[])
+                    .map_err(io::Error::other)?;
+                encoder
+                    .set_repeat(gif::Repeat::Infinite)
+                    .map_err(io::Error::other)?;
+                self.size = (width, height);
+                self.encoder.insert(encoder)
+            }
+        };
+        if (width, height) != self.size {
+            let (gif_width, gif_height) = self.size;
+            return Err(io::Error::other(format!(
+                "a {width}x{height} frame doesn't fit a {gif_width}x{gif_height} GIF"
+            )));
+        }
+        let mut gif_frame =
+            gif::Frame::from_rgb_speed(width, height, image.pixels.as_flattened(), 10);
+        // GIF delays are in hundredths of a second
+        gif_frame.delay = (100 / self.clock.options.fps).max(1) as u16;
+        encoder.write_frame(&gif_frame).map_err(io::Error::other)
+    }
+}
+
+#[cfg(feature = "visualize")]
+impl<W: Write> Visualizer for GifRecorder<W> {
+    fn is_enabled(&self) -> bool {
+        !self.clock.done
+    }
+
+    fn show(&mut self, frame: &Frame) -> Control {
+        if self.clock.tick().is_some() {
+            // a frame the GIF can't hold ends the recording, keeping the frames before it
+            if let Err(e) = self.write_frame(frame) {
+                eprintln!("Error: stopped recording: {e}");
+                self.clock.done = true;
+            }
+        }
+        self.clock.control()
+    }
+}
+
+/// A recorder chosen by the path: `.cast` for asciicast, `.gif` for GIF (with the `visualize`
+/// feature), and anything else is a directory of PNGs.
+pub fn recorder(
+    path: impl AsRef<Path>,
+    options: RecordOptions,
+) -> io::Result<Box<dyn Visualizer + Send>> {
+    let path = path.as_ref();
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("cast") => Ok(Box::new(CastRecorder::create(path, options)?)),
+        #[cfg(feature = "visualize")]
+        Some("gif") => Ok(Box::new(GifRecorder::create(path, options)?)),
+        #[cfg(not(feature = "visualize"))]
+        Some("gif") => Err(io::Error::other(
+            "GIF recording needs the visualize feature",
+        )),
+        _ => Ok(Box::new(PngSequence::create(path, options)?)),
+    }
+}
+
+#[cfg(test)]
+mod record_tests {
+    use super::*;
+    use crate::visualize::Styled;
+
+    const RED: [u8; 3] = [255, 0, 0];
+
+    fn frames(n: usize) -> Vec<Frame> {
+        (0..n)
+            .map(|i| {
+                let mut frame = Frame::new(3, 1);
+                frame.mark([i % 3, 0].into(), '@', RED);
+                frame.set_status(format!("frame {i}"));
+                frame
+            })
+            .collect()
+    }
+
+    #[test]
+    fn cast_test() {
+        let mut cast = CastRecorder::new(vec![], RecordOptions::default());
+        for frame in frames(2) {
+            assert_eq!(cast.show(&frame), Control::Continue);
+        }
+        let text = String::from_utf8(cast.into_inner()).unwrap();
+        let lines: Vec<_> = text.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], r#"{"version": 2, "width": 7, "height": 2}"#);
+        assert!(lines[1].starts_with(r#"[0.000000, "o", "\u001b[H"#));
+        assert!(lines[2].starts_with(r#"[0.033333, "o", "#));
+        assert!(lines[2].ends_with(r#"frame 1"]"#));
+    }
+
+    #[test]
+    fn skip_and_cap_test() {
+        let options = RecordOptions {
+            fps: 10,
+            every: 2,
+            max_duration: Some(Duration::from_millis(200)),
+            ..Default::default()
+        };
+        let mut cast = CastRecorder::new(vec![], options);
+        let controls: Vec<_> = frames(8).iter().map(|f| cast.show(f)).collect();
+        // frames 0, 2 and 4 are kept at 0.0s, 0.1s and 0.2s; frame 6 would be at 0.3s
+        assert_eq!(controls[5], Control::Continue);
+        assert_eq!(controls[6], Control::Quit);
+        assert!(!cast.is_enabled());
+        let text = String::from_utf8(cast.into_inner()).unwrap();
+        assert_eq!(text.lines().count(), 4);
+        assert!(text.contains("frame 4"));
+        assert!(!text.contains("frame 3"));
+    }
+
+    #[test]
+    fn frame_image_test() {
+        let mut frame = Frame::new(2, 1);
+        frame.set(1, 0, Styled::on('#', RED));
+        let image = frame_image(&frame, 4);
+        assert_eq!((image.width, image.height), (8, 4));
+        assert_eq!(image.get(0, 0), Some([0, 0, 0]));
+        assert_eq!(image.get(4, 0), Some(RED));
+        // the character is a block in the middle of the cell, in the foreground colour
+        assert_eq!(image.get(5, 1), Some([255, 255, 255]));
+    }
+
+    #[test]
+    fn png_sequence_test() {
+        let dir = std::env::temp_dir().join("aoc2023_png_sequence_test");
+        let _ = std::fs::remove_dir_all(&dir);
+        let mut pngs = PngSequence::create(&dir, RecordOptions::default()).unwrap();
+        for frame in frames(2) {
+            pngs.show(&frame);
+        }
+        assert!(dir.join("frame_00001.png").exists());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[cfg(feature = "visualize")]
+    #[test]
+    fn gif_test() {
+        let mut gif = GifRecorder::new(vec![], RecordOptions::default());
+        for frame in frames(3) {
+            gif.show(&frame);
+        }
+        let bytes = gif.into_inner();
+        assert_eq!(&bytes[0..6], b"GIF89a");
+    }
+
+    #[cfg(feature = "visualize")]
+    #[test]
+    fn gif_bad_size_test() {
+        let mut gif = GifRecorder::new(vec![], RecordOptions::default());
+        assert_eq!(gif.show(&frames(1)[0]), Control::Continue);
+        // a different size from the first frame
+        assert_eq!(gif.show(&Frame::new(4, 1)), Control::Quit);
+        assert!(!gif.is_enabled());
+        assert_eq!(&gif.into_inner()[0..6], b"GIF89a");
+
+        // too wide for a GIF's 16-bit width
+        let options = RecordOptions {
+            cell_size: 1,
+            ..Default::default()
+        };
+        let mut gif = GifRecorder::new(vec![], options);
+        assert!(gif.write_frame(&Frame::new(70_000, 1)).is_err());
+    }
+}
@@ -0,0 +1,259 @@
+//! Connected regions of a Grid and their measurements, plus tests against closed loops.
+
+use crate::{grid::Grid, point::Point};
+
+/// A group of 4-adjacent cells which all belong together.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Region<T> {
+    /// This region's label in Regions::labels, which is also its index in Regions::regions.
+    pub label: usize,
+    /// The data of the region's first cell, in reading order.
+    pub data: T,
+    /// Every cell in the region, in reading order.
+    pub cells: Vec<Point<2>>,
+    /// The length of the region's outline, counting each cell edge that borders another region
+    /// or the edge of the grid.
+    pub perimeter: usize,
+    /// The number of straight sides in the region's outline, including any holes.
+    pub sides: usize,
+    /// Inclusive min and max corners of the smallest box containing the region.
+    pub bounds: (Point<2>, Point<2>),
+    /// The cells with at least one edge on the region's outline, in reading order.
+    pub border: Vec<Point<2>>,
+}
+
+impl<T> Region<T> {
+    /// The number of cells in the region.
+    pub fn area(&self) -> usize {
+        self.cells.len()
+    }
+}
+
+/// Every region in a grid.  Produced by Grid::regions.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Regions<T: Copy> {
+    /// The label of the region each cell belongs to.
+    pub labels: Grid<usize>,
+    pub regions: Vec<Region<T>>,
+}
+
+impl<T: Copy> Grid<T> {
+    /// Split the grid into regions of 4-adjacent cells for which `eq` returns true, and measure
+    /// each one.
+    pub fn regions(&self, eq: impl Fn(T, T) -> bool) -> Regions<T> {
+        let (labels, count) = self.connected_components(eq);
+
+        let mut regions: Vec<Region<T>> = (0..count)
+            .map(|label| Region {
+                label,
+                data: self.cells[0][0],
+                cells: vec![],
+                perimeter: 0,
+                sides: 0,
+                bounds: (
+                    [usize::MAX, usize::MAX].into(),
+                    [usize::MIN, usize::MIN].into(),
+                ),
+                border: vec![],
+            })
+            .collect();
+
+        // true if (x + dx, y + dy) is in the region with the given label
+        let same = |label: usize, x: usize, y: usize, dx: i64, dy: i64| {
+            let (nx, ny) = (x as i64 + dx, y as i64 + dy);
+            nx >= 0
+                && ny >= 0
+                && labels
+                    .get(nx as usize, ny as usize)
+                    .is_some_and(|l| l == label)
+        };
+
+        for (y, row) in labels.cells.iter().enumerate() {
+            for (x, &label) in row.iter().enumerate() {
+                let region = &mut regions[label];
+                if region.cells.is_empty() {
+                    region.data = self.cells[y][x];
+                }
+                region.cells.push([x, y].into());
+
+                let (min, max) = &mut region.bounds;
+                *min = [min.x().min(x), min.y().min(y)].into();
+                *max = [max.x().max(x), max.y().max(y)].into();
+
+                let open_edges = [(0, -1), (-1, 0), (1, 0), (0, 1)]
+                    .into_iter()
+                    .filter(|(dx, dy)| !same(label, x, y, *dx, *dy))
+                    .count();
+                region.perimeter += open_edges;
+                if open_edges > 0 {
+                    region.border.push([x, y].into());
+                }
+
+                // every side of the outline starts and ends at a corner, so count corners
+                for (dx, dy) in [(-1, -1), (1, -1), (-1, 1), (1, 1)] {
+                    let vertical = same(label, x, y, 0, dy);
+                    let horizontal = same(label, x, y, dx, 0);
+                    let diagonal = same(label, x, y, dx, dy);
+                    let convex = !vertical && !horizontal;
+                    let concave = vertical && horizontal && !diagonal;
+                    if convex || concave {
+                        region.sides += 1;
+                    }
+                }
+            }
+        }
+
+        Regions { labels, regions }
+    }
+}
+
+/// Test whether a point lies strictly inside a closed loop.  The loop is given as its vertices
+/// in order, and the last vertex connects back to the first.  Points on the loop itself are not
+/// inside it.
+pub fn point_in_loop(p: Point<2>, boundary: &[Point<2>]) -> bool {
+    let (px, py) = (p.x() as i64, p.y() as i64);
+    let mut inside = false;
+
+    for (i, a) in boundary.iter().enumerate() {
+        let b = boundary[(i + 1) % boundary.len()];
+        let (ax, ay) = (a.x() as i64, a.y() as i64);
+        let (bx, by) = (b.x() as i64, b.y() as i64);
+
+        // on the segment from a to b
+        let cross = (bx - ax) * (py - ay) - (by - ay) * (px - ax);
+        if cross == 0
+            && px >= ax.min(bx)
+            && px <= ax.max(bx)
+            && py >= ay.min(by)
+            && py <= ay.max(by)
+        {
+            return false;
+        }
+
+        // does a ray cast from p to the right cross this segment?
+        if (ay > py) != (by > py) {
+            // compare px with the x coordinate where the segment meets the ray, without division
+            let lhs = (px - ax) * (by - ay);
+            let rhs = (bx - ax) * (py - ay);
+            if (by > ay && lhs < rhs) || (by < ay && lhs > rhs) {
+                inside = !inside;
+            }
+        }
+    }
+
+    inside
+}
+
+/// Count the cells strictly inside a closed loop of cells, where each cell in the loop is
+/// 4-adjacent to the next and the last is adjacent to the first.  Uses the shoelace formula for
+/// the area enclosed by the cell centers, then Pick's theorem to count the interior cells.  A
+/// boundary too short to enclose anything, or one that isn't closed, encloses nothing.
+pub fn enclosed_cells(boundary: &[Point<2>]) -> usize {
+    // the smallest loop, a 2x2 square, has 4 cells and nothing inside
+    if boundary.len() < 4 {
+        return 0;
+    }
+    // every step, including the one from the last cell back to the first, moves to a neighbour
+    let closed = boundary
+        .iter()
+        .zip(boundary.iter().cycle().skip(1))
+        .all(|(a, b)| a.manhattan(b) == 1);
+    if !closed {
+        return 0;
+    }
+    let mut twice_area: i64 = 0;
+    for (i, a) in boundary.iter().enumerate() {
+        let b = boundary[(i + 1) % boundary.len()];
+        twice_area += a.x() as i64 * b.y() as i64 - b.x() as i64 * a.y() as i64;
+    }
+    let area = twice_area.abs() / 2;
+    usize::try_from(area - boundary.len() as i64 / 2 + 1).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod regions_tests {
+    use super::*;
+
+    #[test]
+    fn regions_test() {
+        let g = Grid::from_rows(["AAAA", "BBCD", "BBCC", "EEEC"]);
+        let regions = g.regions(|a, b| a == b).regions;
+        let summary: Vec<_> = regions
+            .iter()
+            .map(|r| (r.data, r.area(), r.perimeter, r.sides))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ('A', 4, 10, 4),
+                ('B', 4, 8, 4),
+                ('C', 4, 10, 8),
+                ('D', 1, 4, 4),
+                ('E', 3, 8, 4),
+            ]
+        );
+        assert_eq!(regions[2].bounds, ([2, 1].into(), [3, 3].into()));
+    }
+
+    #[test]
+    fn regions_hole_test() {
+        let g = Grid::from_rows(["OOO", "OXO", "OOO"]);
+        let regions = g.regions(|a, b| a == b);
+        let outer = &regions.regions[0];
+        assert_eq!(outer.area(), 8);
+        assert_eq!(outer.perimeter, 16);
+        assert_eq!(outer.sides, 8);
+        assert_eq!(outer.border.len(), 8);
+        assert_eq!(regions.labels.get(1, 1), Some(1));
+    }
+
+    #[test]
+    fn point_in_loop_test() {
+        let square: Vec<Point<2>> =
+            vec![[0, 0].into(), [4, 0].into(), [4, 4].into(), [0, 4].into()];
+        assert!(point_in_loop([2, 2].into(), &square));
+        assert!(!point_in_loop([4, 2].into(), &square));
+        assert!(!point_in_loop([5, 2].into(), &square));
+        // ray passes exactly through a vertex
+        let diamond: Vec<Point<2>> =
+            vec![[2, 0].into(), [4, 2].into(), [2, 4].into(), [0, 2].into()];
+        assert!(point_in_loop([1, 2].into(), &diamond));
+        assert!(!point_in_loop([5, 2].into(), &diamond));
+    }
+
+    #[test]
+    fn enclosed_cells_test() {
+        // a 4x4 ring of cells around a 2x2 interior
+        #[rustfmt::skip]
+        let ring: Vec<Point<2>> = [
+            [0, 0], [1, 0], [2, 0], [3, 0],
+            [3, 1], [3, 2], [3, 3],
+            [2, 3], [1, 3], [0, 3],
+            [0, 2], [0, 1],
+        ]
+        .map(Point::from)
+        .to_vec();
+        assert_eq!(enclosed_cells(&ring), 4);
+    }
+
+    #[test]
+    fn enclosed_cells_degenerate_test() {
+        let points =
+            |coords: &[[usize; 2]]| -> Vec<Point<2>> { coords.iter().map(|&c| c.into()).collect() };
+        assert_eq!(enclosed_cells(&[]), 0);
+        assert_eq!(enclosed_cells(&points(&[[3, 3]])), 0);
+        assert_eq!(
+            enclosed_cells(&points(&[[0, 0], [1, 0], [1, 1], [0, 1]])),
+            0
+        );
+        // a line isn't closed, and Pick's theorem would give a negative count
+        let line = points(&[[0, 0], [1, 0], [2, 0], [3, 0], [4, 0], [5, 0]]);
+        assert_eq!(enclosed_cells(&line), 0);
+        // an open L has a positive shoelace area, but the last cell isn't next to the first
+        let l_path = points(&[[0, 0], [1, 0], [2, 0], [2, 1], [2, 2]]);
+        assert_eq!(enclosed_cells(&l_path), 0);
+        // a gap in the middle of the loop
+        let gap = points(&[[0, 0], [2, 0], [2, 1], [2, 2], [1, 2], [0, 2], [0, 1]]);
+        assert_eq!(enclosed_cells(&gap), 0);
+    }
+}
@@ -0,0 +1,398 @@
+//! Render grids to PNG and SVG image files.
+
+use std::{
+    fmt::Write as _,
+    fs::File,
+    io::{self, BufWriter},
+    path::Path,
+};
+
+use crate::{grid::Grid, point::Point};
+
+/// An RGB colour.
+pub type Rgb = [u8; 3];
+
+/// Decides how each cell is drawn.
+pub trait Palette<T> {
+    /// The background colour of a cell.
+    fn color(&self, t: T) -> Rgb;
+
+    /// A character drawn over the cell, if any.  Only SVG output draws glyphs.
+    fn glyph(&self, _t: T) -> Option<char> {
+        None
+    }
+}
+
+/// Any function from cell data to a colour is a palette with no glyphs.
+impl<T, F: Fn(T) -> Rgb> Palette<T> for F {
+    fn color(&self, t: T) -> Rgb {
+        self(t)
+    }
+}
+
+/// Size and overlays for a rendered grid.
+#[derive(Debug, Clone)]
+pub struct RenderOptions {
+    /// Width and height of each cell, in pixels (PNG) or user units (SVG).
+    pub cell_size: usize,
+    /// Paths drawn as lines through the centers of their cells.
+    pub paths: Vec<(Vec<Point<2>>, Rgb)>,
+    /// Cells outlined in a colour.
+    pub highlights: Vec<(Point<2>, Rgb)>,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            cell_size: 8,
+            paths: vec![],
+            highlights: vec![],
+        }
+    }
+}
+
+impl RenderOptions {
+    pub fn with_path(mut self, path: Vec<Point<2>>, color: Rgb) -> Self {
+        self.paths.push((path, color));
+        self
+    }
+
+    pub fn with_highlight(mut self, p: Point<2>, color: Rgb) -> Self {
+        self.highlights.push((p, color));
+        self
+    }
+}
+
+/// An RGB image, one row after another.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<Rgb>,
+}
+
+impl Image {
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![[0, 0, 0]; width * height],
+        }
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<Rgb> {
+        (x < self.width && y < self.height).then(|| self.pixels[y * self.width + x])
+    }
+
+    /// Set a pixel.  Pixels outside the image are ignored.
+    pub fn set(&mut self, x: i64, y: i64, color: Rgb) {
+        if (0..self.width as i64).contains(&x) && (0..self.height as i64).contains(&y) {
+            self.pixels[y as usize * self.width + x as usize] = color;
+        }
+    }
+
+    /// Fill a rectangle.  Pixels outside the image are ignored.
+    pub fn fill_rect(&mut self, x: i64, y: i64, w: i64, h: i64, color: Rgb) {
+        for py in y..y + h {
+            for px in x..x + w {
+                self.set(px, py, color);
+            }
+        }
+    }
+
+    /// Draw a line with a square brush, using Bresenham's algorithm.
+    fn line(&mut self, from: (i64, i64), to: (i64, i64), thickness: i64, color: Rgb) {
+        let (mut x, mut y) = from;
+        let dx = (to.0 - x).abs();
+        let dy = -(to.1 - y).abs();
+        let sx = (to.0 - x).signum();
+        let sy = (to.1 - y).signum();
+        let mut err = dx + dy;
+        let half = thickness / 2;
+        loop {
+            self.fill_rect(x - half, y - half, thickness, thickness, color);
+            if (x, y) == to {
+                break;
+            }
+            let e2 = 2 * err;
+            if e2 >= dy {
+                err += dy;
+                x += sx;
+            }
+            if e2 <= dx {
+                err += dx;
+                y += sy;
+            }
+        }
+    }
+
+    /// Save as an 8-bit RGB PNG.
+    pub fn save_png(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let file = BufWriter::new(File::create(path)?);
+        let mut encoder = png::Encoder::new(file, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(self.pixels.as_flattened()))
+            .map_err(io::Error::other)
+    }
+}
+
+/// A colour as SVG writes it, like `#ff8000`.
+pub fn hex(color: Rgb) -> String {
+    format!("#{:02x}{:02x}{:02x}", color[0], color[1], color[2])
+}
+
+/// Read a colour written like `#ff8000`.
+pub fn parse_hex(text: &str) -> Option<Rgb> {
+    let digits = text.strip_prefix('#')?;
+    // from_str_radix alone would let a sign through, as in `#+f+f+f`
+    if digits.len() != 6 || !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(digits.get(i..i + 2)?, 16).ok();
+    Some([channel(0)?, channel(2)?, channel(4)?])
+}
+
+fn escape(c: char) -> String {
+    match c {
+        '<' => "&lt;".to_string(),
+        '>' => "&gt;".to_string(),
+        '&' => "&amp;".to_string(),
+        c => c.to_string(),
+    }
+}
+
+impl<T: Copy> Grid<T> {
+    /// Draw the grid, and any overlays, into an image.
+    pub fn to_image(&self, palette: &impl Palette<T>, options: &RenderOptions) -> Image {
+        let size = options.cell_size as i64;
+        let mut image = Image::new(
+            self.width() * options.cell_size,
+            self.height() * options.cell_size,
+        );
+
+        for (y, row) in self.cells.iter().enumerate() {
+            for (x, t) in row.iter().enumerate() {
+                let color = palette.color(*t);
+                image.fill_rect(x as i64 * size, y as i64 * size, size, size, color);
+            }
+        }
+
+        let center = |p: &Point<2>| {
+            (
+                p.x() as i64 * size + size / 2,
+                p.y() as i64 * size + size / 2,
+            )
+        };
+        for (path, color) in &options.paths {
+            for pair in path.windows(2) {
+                image.line(
+                    center(&pair[0]),
+                    center(&pair[1]),
+                    (size / 4).max(1),
+                    *color,
+                );
+            }
+        }
+
+        let border = (size / 8).max(1);
+        for (p, color) in &options.highlights {
+            let (x, y) = (p.x() as i64 * size, p.y() as i64 * size);
+            image.fill_rect(x, y, size, border, *color);
+            image.fill_rect(x, y + size - border, size, border, *color);
+            image.fill_rect(x, y, border, size, *color);
+            image.fill_rect(x + size - border, y, border, size, *color);
+        }
+
+        image
+    }
+
+    /// Render the grid to a PNG file.
+    pub fn render_png(&self, path: impl AsRef<Path>, palette: &impl Palette<T>) -> io::Result<()> {
+        self.render_png_with(path, palette, &RenderOptions::default())
+    }
+
+    /// Render the grid to a PNG file with the given cell size and overlays.
+    pub fn render_png_with(
+        &self,
+        path: impl AsRef<Path>,
+        palette: &impl Palette<T>,
+        options: &RenderOptions,
+    ) -> io::Result<()> {
+        self.to_image(palette, options).save_png(path)
+    }
+
+    /// Draw the grid, and any overlays, as an SVG document.
+    pub fn to_svg(&self, palette: &impl Palette<T>, options: &RenderOptions) -> String {
+        let size = options.cell_size;
+        let mut svg = String::new();
+        writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+            w = self.width() * size,
+            h = self.height() * size,
+        )
+        .unwrap();
+
+        for (y, row) in self.cells.iter().enumerate() {
+            for (x, t) in row.iter().enumerate() {
+                writeln!(
+                    svg,
+                    r#"<rect x="{}" y="{}" width="{size}" height="{size}" fill="{}"/>"#,
+                    x * size,
+                    y * size,
+                    hex(palette.color(*t)),
+                )
+                .unwrap();
+                if let Some(glyph) = palette.glyph(*t) {
+                    writeln!(
+                        svg,
+                        r#"<text x="{}" y="{}" font-size="{size}" font-family="monospace" text-anchor="middle" dominant-baseline="central">{}</text>"#,
+                        x * size + size / 2,
+                        y * size + size / 2,
+                        escape(glyph),
+                    )
+                    .unwrap();
+                }
+            }
+        }
+
+        for (path, color) in &options.paths {
+            let points: Vec<String> = path
+                .iter()
+                .map(|p| format!("{},{}", p.x() * size + size / 2, p.y() * size + size / 2))
+                .collect();
+            writeln!(
+                svg,
+                r#"<polyline points="{}" fill="none" stroke="{}" stroke-width="{}" stroke-linejoin="round"/>"#,
+                points.join(" "),
+                hex(*color),
+                (size / 4).max(1),
+            )
+            .unwrap();
+        }
+
+        for (p, color) in &options.highlights {
+            writeln!(
+                svg,
+                r#"<rect x="{}" y="{}" width="{size}" height="{size}" fill="none" stroke="{}"/>"#,
+                p.x() * size,
+                p.y() * size,
+                hex(*color),
+            )
+            .unwrap();
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// Render the grid to an SVG file.
+    pub fn render_svg(&self, path: impl AsRef<Path>, palette: &impl Palette<T>) -> io::Result<()> {
+        self.render_svg_with(path, palette, &RenderOptions::default())
+    }
+
+    /// Render the grid to an SVG file with the given cell size and overlays.
+    pub fn render_svg_with(
+        &self,
+        path: impl AsRef<Path>,
+        palette: &impl Palette<T>,
+        options: &RenderOptions,
+    ) -> io::Result<()> {
+        std::fs::write(path, self.to_svg(palette, options))
+    }
+}
+
+#[cfg(test)]
+mod render_tests {
+    use super::*;
+
+    const BLACK: Rgb = [0, 0, 0];
+    const WHITE: Rgb = [255, 255, 255];
+    const RED: Rgb = [255, 0, 0];
+
+    fn checker() -> Grid<bool> {
+        Grid::new(vec![vec![true, false], vec![false, true]])
+    }
+
+    fn bw(on: bool) -> Rgb {
+        if on {
+            WHITE
+        } else {
+            BLACK
+        }
+    }
+
+    #[test]
+    fn to_image_test() {
+        let options = RenderOptions {
+            cell_size: 2,
+            ..Default::default()
+        };
+        let image = checker().to_image(&bw, &options);
+        assert_eq!((image.width, image.height), (4, 4));
+        assert_eq!(image.get(0, 0), Some(WHITE));
+        assert_eq!(image.get(1, 1), Some(WHITE));
+        assert_eq!(image.get(2, 1), Some(BLACK));
+        assert_eq!(image.get(3, 3), Some(WHITE));
+    }
+
+    #[test]
+    fn to_image_overlay_test() {
+        let options = RenderOptions {
+            cell_size: 4,
+            ..Default::default()
+        }
+        .with_path(vec![[0, 0].into(), [1, 0].into()], RED);
+        let image = checker().to_image(&bw, &options);
+        // the path runs along the middle of the top row of cells
+        assert_eq!(image.get(2, 2), Some(RED));
+        assert_eq!(image.get(6, 2), Some(RED));
+        assert_eq!(image.get(6, 0), Some(BLACK));
+    }
+
+    #[test]
+    fn hex_test() {
+        assert_eq!(hex([255, 128, 0]), "#ff8000");
+        assert_eq!(parse_hex("#70c710"), Some([0x70, 0xc7, 0x10]));
+        assert_eq!(parse_hex("#70c71"), None);
+        assert_eq!(parse_hex("70c710"), None);
+        assert_eq!(parse_hex("#70g710"), None);
+        assert_eq!(parse_hex("#+f+f+f"), None);
+    }
+
+    #[test]
+    fn to_svg_test() {
+        struct Glyphs;
+        impl Palette<bool> for Glyphs {
+            fn color(&self, t: bool) -> Rgb {
+                bw(t)
+            }
+            fn glyph(&self, t: bool) -> Option<char> {
+                t.then_some('<')
+            }
+        }
+
+        let svg = checker().to_svg(
+            &Glyphs,
+            &RenderOptions::default().with_highlight([1, 0].into(), RED),
+        );
+        assert!(svg.starts_with("<svg"));
+        assert_eq!(svg.matches("<rect").count(), 5);
+        assert_eq!(svg.matches("&lt;").count(), 2);
+        assert!(svg.contains(
+            r##"<rect x="8" y="0" width="8" height="8" fill="none" stroke="#ff0000"/>"##
+        ));
+    }
+
+    #[test]
+    fn render_png_test() {
+        let path = std::env::temp_dir().join("aoc2023_render_png_test.png");
+        checker().render_png(&path, &bw).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        assert_eq!(&bytes[1..4], b"PNG");
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,263 @@
+use std::{collections::HashMap, fmt::Display};
+
+use crate::{
+    grid::{Adj4, Adj8, Cell, Grid, GridLike},
+    point::IPoint,
+};
+
+/// A grid that stores only the cells that have been set, keyed by signed position.  The bounding
+/// box grows (and shrinks) as cells are inserted and removed, so there's no need to know the
+/// extent of the grid up front or to offset coordinates to keep them positive.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SparseGrid<T: Copy> {
+    pub cells: HashMap<IPoint<2>, T>,
+    /// The character displayed for positions within the bounds that have no cell.
+    pub fill: char,
+    /// Inclusive min and max corners of the bounding box, or None if the grid is empty.
+    bounds: Option<(IPoint<2>, IPoint<2>)>,
+}
+
+impl<T: Copy> Default for SparseGrid<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Copy> SparseGrid<T> {
+    pub fn new() -> Self {
+        Self::with_fill('.')
+    }
+
+    /// Create an empty grid which displays empty positions with the given character.
+    pub fn with_fill(fill: char) -> Self {
+        Self {
+            cells: HashMap::new(),
+            fill,
+            bounds: None,
+        }
+    }
+
+    /// Build a sparse grid from the cells of a dense grid for which `keep` returns true.
+    pub fn from_grid(grid: &Grid<T>, keep: impl Fn(T) -> bool) -> Self {
+        let mut sparse = Self::new();
+        for (y, row) in grid.cells.iter().enumerate() {
+            for (x, t) in row.iter().enumerate() {
+                if keep(*t) {
+                    sparse.insert(x as i64, y as i64, *t);
+                }
+            }
+        }
+        sparse
+    }
+
+    /// Set the data at the given position, returning the data previously stored there, if any.
+    pub fn insert(&mut self, x: i64, y: i64, t: T) -> Option<T> {
+        self.bounds = Some(match self.bounds {
+            Some((min, max)) => (
+                [min.x().min(x), min.y().min(y)].into(),
+                [max.x().max(x), max.y().max(y)].into(),
+            ),
+            None => ([x, y].into(), [x, y].into()),
+        });
+        self.cells.insert([x, y].into(), t)
+    }
+
+    /// Remove the data at the given position, returning it if there was any.
+    pub fn remove(&mut self, x: i64, y: i64) -> Option<T> {
+        let removed = self.cells.remove(&[x, y].into());
+        if let (Some(_), Some((min, max))) = (removed, self.bounds) {
+            // only a cell on the edge of the bounding box can shrink it
+            if x == min.x() || x == max.x() || y == min.y() || y == max.y() {
+                self.recompute_bounds();
+            }
+        }
+        removed
+    }
+
+    fn recompute_bounds(&mut self) {
+        self.bounds = self.cells.keys().fold(None, |bounds, p| {
+            Some(match bounds {
+                Some((min, max)) => (
+                    [min.x().min(p.x()), min.y().min(p.y())].into(),
+                    [max.x().max(p.x()), max.y().max(p.y())].into(),
+                ),
+                None => (*p, *p),
+            })
+        });
+    }
+
+    pub fn contains(&self, x: i64, y: i64) -> bool {
+        self.cells.contains_key(&[x, y].into())
+    }
+
+    /// The number of cells that have been set.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Inclusive min and max corners of the smallest box containing every cell, or None if the
+    /// grid is empty.
+    pub fn bounds(&self) -> Option<(IPoint<2>, IPoint<2>)> {
+        self.bounds
+    }
+
+    pub fn width(&self) -> usize {
+        self.bounds
+            .map(|(min, max)| (max.x() - min.x() + 1) as usize)
+            .unwrap_or(0)
+    }
+
+    pub fn height(&self) -> usize {
+        self.bounds
+            .map(|(min, max)| (max.y() - min.y() + 1) as usize)
+            .unwrap_or(0)
+    }
+
+    /// Iterate over every cell that has been set, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = Cell<T, IPoint<2>>> + '_ {
+        self.cells.iter().map(|(pos, t)| Cell::new(*pos, *t))
+    }
+
+    /// Convert to a dense grid covering the bounding box, using `empty` for positions that have
+    /// no cell.  The min corner of the bounding box becomes (0, 0).
+    pub fn to_grid(&self, empty: T) -> Grid<T> {
+        let Some((min, max)) = self.bounds else {
+            return Grid::new(vec![]);
+        };
+        Grid::new(
+            (min.y()..=max.y())
+                .map(|y| {
+                    (min.x()..=max.x())
+                        .map(|x| self.cells.get(&[x, y].into()).copied().unwrap_or(empty))
+                        .collect()
+                })
+                .collect(),
+        )
+    }
+
+    fn cells_at<const N: usize>(
+        &self,
+        positions: [(i64, i64); N],
+    ) -> [Option<Cell<T, IPoint<2>>>; N] {
+        positions.map(|(x, y)| {
+            self.cells
+                .get(&[x, y].into())
+                .map(|t| Cell::new([x, y].into(), *t))
+        })
+    }
+}
+
+impl<T: Copy> GridLike<T> for SparseGrid<T> {
+    type Coord = i64;
+    type Pos = IPoint<2>;
+
+    fn get(&self, x: i64, y: i64) -> Option<T> {
+        self.cells.get(&[x, y].into()).copied()
+    }
+
+    fn adj_4(&self, x: i64, y: i64) -> Adj4<T, IPoint<2>> {
+        Adj4::new(self.cells_at([(x, y - 1), (x - 1, y), (x + 1, y), (x, y + 1)]))
+    }
+
+    fn adj_8(&self, x: i64, y: i64) -> Adj8<T, IPoint<2>> {
+        Adj8::new(self.cells_at([
+            (x - 1, y - 1),
+            (x, y - 1),
+            (x + 1, y - 1),
+            (x - 1, y),
+            (x + 1, y),
+            (x - 1, y + 1),
+            (x, y + 1),
+            (x + 1, y + 1),
+        ]))
+    }
+
+    fn render(&self, fill: char) -> String
+    where
+        T: Display,
+    {
+        let mut out = String::new();
+        if let Some((min, max)) = self.bounds {
+            for y in min.y()..=max.y() {
+                for x in min.x()..=max.x() {
+                    match self.cells.get(&[x, y].into()) {
+                        Some(t) => out.push_str(&t.to_string()),
+                        None => out.push(fill),
+                    }
+                }
+                out.push('\n');
+            }
+        }
+        out
+    }
+}
+
+impl<T> Display for SparseGrid<T>
+where
+    T: Display + Copy,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.render(self.fill))
+    }
+}
+
+#[cfg(test)]
+mod sparse_grid_tests {
+    use super::*;
+
+    #[test]
+    fn sparse_bounds_test() {
+        let mut g = SparseGrid::new();
+        assert_eq!(g.bounds(), None);
+
+        g.insert(0, 0, 'a');
+        g.insert(-3, 2, 'b');
+        g.insert(4, -1, 'c');
+        assert_eq!(g.bounds(), Some(([-3, -1].into(), [4, 2].into())));
+        assert_eq!((g.width(), g.height()), (8, 4));
+
+        g.remove(-3, 2);
+        assert_eq!(g.bounds(), Some(([0, -1].into(), [4, 0].into())));
+
+        g.remove(0, 0);
+        g.remove(4, -1);
+        assert_eq!(g.bounds(), None);
+    }
+
+    #[test]
+    fn sparse_adj_4_test() {
+        let mut g = SparseGrid::new();
+        g.insert(-1, 0, 1);
+        g.insert(0, 1, 2);
+        assert_eq!(
+            g.adj_4(0, 0),
+            Adj4::new([
+                None,
+                Some(Cell::new([-1, 0].into(), 1)),
+                None,
+                Some(Cell::new([0, 1].into(), 2)),
+            ])
+        );
+    }
+
+    #[test]
+    fn sparse_display_test() {
+        let mut g = SparseGrid::with_fill(' ');
+        g.insert(-1, -1, '#');
+        g.insert(1, 0, '#');
+        assert_eq!(g.to_string(), "#  \n  #\n");
+        assert_eq!(g.render('.'), "#..\n..#\n");
+    }
+
+    #[test]
+    fn sparse_dense_round_trip_test() {
+        let dense = Grid::new(vec![vec![0, 1, 0], vec![0, 0, 2]]);
+        let sparse = SparseGrid::from_grid(&dense, |t| t != 0);
+        assert_eq!(sparse.len(), 2);
+        assert_eq!(sparse.to_grid(0).cells, vec![vec![1, 0], vec![0, 2]]);
+    }
+}
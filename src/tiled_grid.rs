@@ -0,0 +1,181 @@
+use std::fmt::Display;
+
+use crate::{
+    grid::{Cell, Grid},
+    point::{IPoint, Point},
+};
+
+/// A grid that repeats infinitely in every direction.  The wrapped grid is tile (0, 0), and
+/// coordinates outside it wrap around to the matching cell of the original grid.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct TiledGrid<T: Copy> {
+    pub grid: Grid<T>,
+}
+
+impl<T: Copy> TiledGrid<T> {
+    pub fn new(grid: Grid<T>) -> Self {
+        assert!(grid.area() > 0, "can't tile an empty grid");
+        Self { grid }
+    }
+
+    /// Width of one tile.
+    pub fn width(&self) -> usize {
+        self.grid.width()
+    }
+
+    /// Height of one tile.
+    pub fn height(&self) -> usize {
+        self.grid.height()
+    }
+
+    /// Convert a position on the infinite plane to a position within the original grid.
+    pub fn wrap(&self, x: i64, y: i64) -> Point<2> {
+        [
+            x.rem_euclid(self.width() as i64) as usize,
+            y.rem_euclid(self.height() as i64) as usize,
+        ]
+        .into()
+    }
+
+    /// Find which tile a position lies in.  The original grid is tile (0, 0), the copy to its
+    /// left is tile (-1, 0), and so on.
+    pub fn tile_of(&self, x: i64, y: i64) -> IPoint<2> {
+        [
+            x.div_euclid(self.width() as i64),
+            y.div_euclid(self.height() as i64),
+        ]
+        .into()
+    }
+
+    /// Get the cell data at any position.  Never fails, since every position maps to a cell.
+    pub fn get(&self, x: i64, y: i64) -> T {
+        let p = self.wrap(x, y);
+        self.grid.cells[p.y()][p.x()]
+    }
+
+    /// Get cells adjacent to the given point in the cardinal directions.  Same ordering as
+    /// Grid::adj_4, but since the grid is unbounded, every neighbour exists.
+    ///
+    /// # Ordering
+    ///
+    /// ```text
+    /// [ ⬆️, ⬅️, ➡️, ⬇️, ]
+    /// ```
+    pub fn adj_4(&self, x: i64, y: i64) -> [Cell<T, IPoint<2>>; 4] {
+        [(x, y - 1), (x - 1, y), (x + 1, y), (x, y + 1)]
+            .map(|(adj_x, adj_y)| Cell::new([adj_x, adj_y].into(), self.get(adj_x, adj_y)))
+    }
+
+    /// Get cells adjacent to the given point in cardinal and ordinal directions.  Same ordering
+    /// as Grid::adj_8, but since the grid is unbounded, every neighbour exists.
+    ///
+    /// # Ordering
+    ///
+    /// ```text
+    /// [
+    ///   ↖️, ⬆️, ↗️,
+    ///   ⬅️,    ➡️,
+    ///   ↙️, ⬇️, ↘️,
+    /// ]
+    /// ```
+    pub fn adj_8(&self, x: i64, y: i64) -> [Cell<T, IPoint<2>>; 8] {
+        [
+            (x - 1, y - 1),
+            (x, y - 1),
+            (x + 1, y - 1),
+            (x - 1, y),
+            (x + 1, y),
+            (x - 1, y + 1),
+            (x, y + 1),
+            (x + 1, y + 1),
+        ]
+        .map(|(adj_x, adj_y)| Cell::new([adj_x, adj_y].into(), self.get(adj_x, adj_y)))
+    }
+
+    /// Copy an n×n block of tiles into a regular grid, for debugging.  The block is centered on
+    /// tile (0, 0) when n is odd; when n is even it extends one tile further right and down.
+    pub fn materialize(&self, n: usize) -> Grid<T> {
+        let first_tile = -((n as i64 - 1) / 2);
+        let x0 = first_tile * self.width() as i64;
+        let y0 = first_tile * self.height() as i64;
+
+        Grid::new(
+            (0..(n * self.height()) as i64)
+                .map(|y| {
+                    (0..(n * self.width()) as i64)
+                        .map(|x| self.get(x0 + x, y0 + y))
+                        .collect()
+                })
+                .collect(),
+        )
+    }
+}
+
+impl<T> Display for TiledGrid<T>
+where
+    T: Display + Copy,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.grid)
+    }
+}
+
+#[cfg(test)]
+mod tiled_grid_tests {
+    use super::*;
+
+    fn tiles() -> TiledGrid<u8> {
+        TiledGrid::new(Grid::new(vec![vec![1, 2], vec![3, 4]]))
+    }
+
+    #[test]
+    fn tiled_get_test() {
+        let t = tiles();
+        assert_eq!(t.get(0, 0), 1);
+        assert_eq!(t.get(-1, 0), 2);
+        assert_eq!(t.get(-1, -1), 4);
+        assert_eq!(t.get(5, 6), 2);
+    }
+
+    #[test]
+    fn tiled_tile_of_test() {
+        let t = tiles();
+        assert_eq!(t.tile_of(0, 0), [0, 0].into());
+        assert_eq!(t.tile_of(1, 1), [0, 0].into());
+        assert_eq!(t.tile_of(-1, 0), [-1, 0].into());
+        assert_eq!(t.tile_of(-2, 2), [-1, 1].into());
+        assert_eq!(t.tile_of(-3, -5), [-2, -3].into());
+    }
+
+    #[test]
+    fn tiled_adj_4_test() {
+        let t = tiles();
+        assert_eq!(
+            t.adj_4(0, 0),
+            [
+                Cell::new([0, -1].into(), 3),
+                Cell::new([-1, 0].into(), 2),
+                Cell::new([1, 0].into(), 2),
+                Cell::new([0, 1].into(), 3),
+            ]
+        );
+    }
+
+    #[test]
+    fn tiled_materialize_test() {
+        let t = tiles();
+        #[rustfmt::skip]
+        assert_eq!(
+            t.materialize(3).cells,
+            vec![
+                vec![1, 2, 1, 2, 1, 2],
+                vec![3, 4, 3, 4, 3, 4],
+                vec![1, 2, 1, 2, 1, 2],
+                vec![3, 4, 3, 4, 3, 4],
+                vec![1, 2, 1, 2, 1, 2],
+                vec![3, 4, 3, 4, 3, 4],
+            ]
+        );
+        assert_eq!(t.materialize(1), t.grid);
+    }
+}
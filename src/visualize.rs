@@ -0,0 +1,650 @@
+//! Animated views of a solution as it runs.
+//!
+//! A solution builds a `Frame` for each step it wants to show and hands it to a `Visualizer`,
+//! which decides what to do with it.  With the `visualize` feature on, `live` opens a terminal
+//! view; with it off, `live` returns a visualizer which ignores everything, so the frame building
+//! (guarded by `is_enabled`) compiles away.
+
+use std::{
+    fmt::Display,
+    io,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use crate::{
+    grid::Grid,
+    point::Point,
+    record::RecordOptions,
+    render::{Palette, Rgb},
+};
+
+pub const BLACK: Rgb = [0, 0, 0];
+pub const WHITE: Rgb = [255, 255, 255];
+pub const CYAN: Rgb = [0, 255, 255];
+pub const GREEN: Rgb = [0, 160, 0];
+pub const DARK_RED: Rgb = [128, 0, 0];
+pub const DARK_BLUE: Rgb = [0, 0, 128];
+
+/// How one cell of a frame looks: a character in a foreground colour on a background colour.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Styled {
+    pub ch: char,
+    pub fg: Rgb,
+    pub bg: Rgb,
+}
+
+impl Styled {
+    /// A character on a background, in whichever of black or white shows up better on it.
+    pub fn on(ch: char, bg: Rgb) -> Self {
+        Self {
+            ch,
+            fg: contrast(bg),
+            bg,
+        }
+    }
+}
+
+impl Default for Styled {
+    fn default() -> Self {
+        Self::on(' ', BLACK)
+    }
+}
+
+/// Black or white, whichever contrasts more with `bg`.
+fn contrast(bg: Rgb) -> Rgb {
+    let luma = 299 * bg[0] as u32 + 587 * bg[1] as u32 + 114 * bg[2] as u32;
+    if luma > 128_000 {
+        BLACK
+    } else {
+        WHITE
+    }
+}
+
+/// One picture in an animation: a rectangle of styled cells plus a line of status text.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Frame {
+    pub width: usize,
+    pub height: usize,
+    /// One row after another.
+    pub cells: Vec<Styled>,
+    /// Shown below the cells.
+    pub status: String,
+}
+
+impl Frame {
+    /// A blank frame.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![Styled::default(); width * height],
+            status: String::new(),
+        }
+    }
+
+    /// A frame showing a grid, one cell per cell.  The palette's colour is the background and its
+    /// glyph, if any, the character.
+    pub fn from_grid<T: Copy>(grid: &Grid<T>, palette: &impl Palette<T>) -> Self {
+        let mut frame = Self::new(grid.width(), grid.height());
+        for (y, row) in grid.cells.iter().enumerate() {
+            for (x, t) in row.iter().enumerate() {
+                let ch = palette.glyph(*t).unwrap_or(' ');
+                frame.set(x, y, Styled::on(ch, palette.color(*t)));
+            }
+        }
+        frame
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<Styled> {
+        (x < self.width && y < self.height).then(|| self.cells[y * self.width + x])
+    }
+
+    /// Set a cell.  Cells outside the frame are ignored.
+    pub fn set(&mut self, x: usize, y: usize, styled: Styled) {
+        if x < self.width && y < self.height {
+            self.cells[y * self.width + x] = styled;
+        }
+    }
+
+    /// Change a cell's background, keeping its character.
+    pub fn highlight(&mut self, p: Point<2>, bg: Rgb) {
+        if let Some(styled) = self.get(p.x(), p.y()) {
+            self.set(p.x(), p.y(), Styled::on(styled.ch, bg));
+        }
+    }
+
+    /// Replace a cell with a marker character on a background.
+    pub fn mark(&mut self, p: Point<2>, ch: char, bg: Rgb) {
+        self.set(p.x(), p.y(), Styled::on(ch, bg));
+    }
+
+    pub fn set_status(&mut self, status: impl Into<String>) {
+        self.status = status.into();
+    }
+
+    /// Add a row of text below the cells, cut or padded to the frame's width.
+    pub fn push_row(&mut self, text: &str, bg: Rgb) {
+        let mut chars = text.chars();
+        self.cells
+            .extend((0..self.width).map(|_| Styled::on(chars.next().unwrap_or(' '), bg)));
+        self.height += 1;
+    }
+
+    /// The characters of one row, without colour.
+    pub fn row_text(&self, y: usize) -> String {
+        self.cells[y * self.width..(y + 1) * self.width]
+            .iter()
+            .map(|styled| styled.ch)
+            .collect()
+    }
+}
+
+/// The characters of the frame without colour, then the status line if there is one.
+impl Display for Frame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for y in 0..self.height {
+            writeln!(f, "{}", self.row_text(y))?;
+        }
+        if !self.status.is_empty() {
+            writeln!(f, "{}", self.status)?;
+        }
+        Ok(())
+    }
+}
+
+/// A tiny bar chart of `values`, one block character per value, scaled so the smallest is the
+/// lowest block and the largest the highest.
+pub fn sparkline(values: &[usize]) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let (Some(&min), Some(&max)) = (values.iter().min(), values.iter().max()) else {
+        return String::new();
+    };
+    let range = (max - min).max(1);
+    values
+        .iter()
+        .map(|&v| BARS[(v - min) * (BARS.len() - 1) / range])
+        .collect()
+}
+
+/// What the viewer wants after seeing a frame.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Control {
+    Continue,
+    /// The viewer has had enough.  The visualizer is disabled from here on, and the solution may
+    /// stop early or carry on without showing anything.
+    Quit,
+}
+
+/// Somewhere to send frames.
+pub trait Visualizer {
+    /// Whether frames are wanted.  Solutions should skip building frames when this is false.
+    fn is_enabled(&self) -> bool {
+        true
+    }
+
+    /// Show a frame.  Interactive backends may block here while the viewer has paused.
+    fn show(&mut self, frame: &Frame) -> Control;
+}
+
+impl<V: Visualizer + ?Sized> Visualizer for &mut V {
+    fn is_enabled(&self) -> bool {
+        (**self).is_enabled()
+    }
+
+    fn show(&mut self, frame: &Frame) -> Control {
+        (**self).show(frame)
+    }
+}
+
+impl<V: Visualizer + ?Sized> Visualizer for Box<V> {
+    fn is_enabled(&self) -> bool {
+        (**self).is_enabled()
+    }
+
+    fn show(&mut self, frame: &Frame) -> Control {
+        (**self).show(frame)
+    }
+}
+
+/// An optional visualizer, which is disabled when absent.
+impl<V: Visualizer> Visualizer for Option<V> {
+    fn is_enabled(&self) -> bool {
+        self.as_ref().is_some_and(|v| v.is_enabled())
+    }
+
+    fn show(&mut self, frame: &Frame) -> Control {
+        match self {
+            Some(v) => v.show(frame),
+            None => Control::Quit,
+        }
+    }
+}
+
+/// Sends each frame to both visualizers, eg. to watch and record at once.  Only quits once both
+/// have.
+impl<A: Visualizer, B: Visualizer> Visualizer for (A, B) {
+    fn is_enabled(&self) -> bool {
+        self.0.is_enabled() || self.1.is_enabled()
+    }
+
+    fn show(&mut self, frame: &Frame) -> Control {
+        if self.0.is_enabled() {
+            self.0.show(frame);
+        }
+        if self.1.is_enabled() {
+            self.1.show(frame);
+        }
+        if self.is_enabled() {
+            Control::Continue
+        } else {
+            Control::Quit
+        }
+    }
+}
+
+/// Keeps frames in memory instead of showing them, for tests and anywhere else without a
+/// terminal.
+#[derive(Debug, Clone, Default)]
+pub struct Capture {
+    frames: Vec<Frame>,
+    /// The number of frames shown, including any dropped by keep_last.
+    shown: usize,
+    keep_last: Option<usize>,
+}
+
+impl Capture {
+    /// Keep every frame.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep only the most recent `n` frames, for long runs.
+    pub fn keep_last(n: usize) -> Self {
+        Self {
+            keep_last: Some(n),
+            ..Self::default()
+        }
+    }
+
+    /// The number of frames shown, including any no longer kept.
+    pub fn shown(&self) -> usize {
+        self.shown
+    }
+
+    /// The frames still kept, oldest first.
+    pub fn frames(&self) -> &[Frame] {
+        &self.frames
+    }
+
+    /// The nth frame shown, counting from 0, if it's still kept.
+    pub fn frame(&self, n: usize) -> Option<&Frame> {
+        let first_kept = self.shown - self.frames.len();
+        self.frames.get(n.checked_sub(first_kept)?)
+    }
+
+    pub fn last(&self) -> Option<&Frame> {
+        self.frames.last()
+    }
+}
+
+impl Visualizer for Capture {
+    fn show(&mut self, frame: &Frame) -> Control {
+        self.frames.push(frame.clone());
+        self.shown += 1;
+        if self.keep_last.is_some_and(|n| self.frames.len() > n) {
+            self.frames.remove(0);
+        }
+        Control::Continue
+    }
+}
+
+/// Compare a frame's text (see Frame's Display) with the snapshot file `snapshots/<name>.txt`.
+/// A missing snapshot fails the test.  When the UPDATE_SNAPSHOTS environment variable is set,
+/// snapshots are written instead of compared, so check new and changed ones before committing.
+#[track_caller]
+pub fn assert_snapshot(name: &str, frame: &Frame) {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("snapshots")
+        .join(format!("{name}.txt"));
+    check_snapshot(&path, frame, std::env::var_os("UPDATE_SNAPSHOTS").is_some());
+}
+
+#[track_caller]
+fn check_snapshot(path: &Path, frame: &Frame, update: bool) {
+    let actual = frame.to_string();
+
+    if update {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, &actual).unwrap();
+        return;
+    }
+
+    let Ok(expected) = std::fs::read_to_string(path) else {
+        panic!(
+            "snapshot missing (set UPDATE_SNAPSHOTS=1 to create it): {}",
+            path.display()
+        );
+    };
+    if actual != expected {
+        panic!(
+            "frame doesn't match snapshot {} (set UPDATE_SNAPSHOTS=1 to accept it)\n\
+             expected:\n{expected}\nactual:\n{actual}",
+            path.display()
+        );
+    }
+}
+
+/// Ignores every frame.
+#[derive(Debug, Copy, Clone, Default)]
+pub struct NoVisualizer;
+
+impl Visualizer for NoVisualizer {
+    fn is_enabled(&self) -> bool {
+        false
+    }
+
+    fn show(&mut self, _frame: &Frame) -> Control {
+        Control::Continue
+    }
+}
+
+/// Where a shared recorder lives, so every handle on it records into the same file.
+type RecorderSlot = Mutex<Option<Box<dyn Visualizer + Send>>>;
+
+/// The recorder started by record_to, if any.
+static RECORDING: RecorderSlot = Mutex::new(None);
+
+/// Make every visualizer from `live` also record its frames to `path`.  See record::recorder for
+/// the formats.  The recorder is started here and shared, so frames from every run after this
+/// end up in the one recording; call finish_recording once they're all done.  Has no effect
+/// on `live` without the `visualize` feature.
+pub fn record_to(path: impl AsRef<Path>, options: RecordOptions) -> io::Result<()> {
+    *RECORDING.lock().unwrap() = Some(crate::record::recorder(path, options)?);
+    Ok(())
+}
+
+/// Finish the recording started by record_to, flushing it to its file.
+pub fn finish_recording() {
+    RECORDING.lock().unwrap().take();
+}
+
+/// A handle on the recorder started by record_to, passing frames on to it.
+pub struct SharedRecording {
+    slot: &'static RecorderSlot,
+}
+
+impl SharedRecording {
+    /// A handle, if record_to has started a recording.
+    pub fn get() -> Option<Self> {
+        Self::in_slot(&RECORDING)
+    }
+
+    fn in_slot(slot: &'static RecorderSlot) -> Option<Self> {
+        slot.lock().unwrap().is_some().then_some(Self { slot })
+    }
+}
+
+impl Visualizer for SharedRecording {
+    fn is_enabled(&self) -> bool {
+        self.slot
+            .lock()
+            .unwrap()
+            .as_ref()
+            .is_some_and(|recorder| recorder.is_enabled())
+    }
+
+    fn show(&mut self, frame: &Frame) -> Control {
+        match self.slot.lock().unwrap().as_mut() {
+            Some(recorder) => recorder.show(frame),
+            None => Control::Quit,
+        }
+    }
+}
+
+/// With the `visualize` feature on, the terminal view plus a recorder if record_to was called.
+/// The terminal view is left out when stdout isn't a terminal, so recordings can be made
+/// headless.  With the feature off, a NoVisualizer.
+#[cfg(all(feature = "visualize", not(test)))]
+pub fn live() -> (Option<ConsoleVisualizer>, Option<SharedRecording>) {
+    use std::io::IsTerminal;
+
+    let console = std::io::stdout()
+        .is_terminal()
+        .then(|| ConsoleVisualizer::new(144));
+    (console, SharedRecording::get())
+}
+
+/// Tests run the solutions in parallel with the terminal attached, so they get neither the
+/// terminal view nor the process-wide recording.
+#[cfg(all(feature = "visualize", test))]
+pub fn live() -> (Option<ConsoleVisualizer>, Option<SharedRecording>) {
+    (None, None)
+}
+
+/// With the `visualize` feature on, the terminal view plus a recorder if record_to was called.
+/// With the feature off, a NoVisualizer.
+#[cfg(not(feature = "visualize"))]
+pub fn live() -> NoVisualizer {
+    NoVisualizer
+}
+
+/// Shows frames in the terminal using console_engine.  Space pauses and resumes, n steps one
+/// frame while paused, and q quits.
+#[cfg(feature = "visualize")]
+pub struct ConsoleVisualizer {
+    fps: u32,
+    /// Started on the first frame, so the screen can be sized to fit it.
+    engine: Option<console_engine::ConsoleEngine>,
+    paused: bool,
+    quit: bool,
+}
+
+#[cfg(feature = "visualize")]
+impl ConsoleVisualizer {
+    pub fn new(fps: u32) -> Self {
+        Self {
+            fps,
+            engine: None,
+            paused: false,
+            quit: false,
+        }
+    }
+
+    /// Check the keyboard, returning true if the viewer quit.
+    fn handle_keys(&mut self) -> bool {
+        use console_engine::KeyCode;
+
+        let engine = self.engine.as_mut().unwrap();
+        if engine.is_key_pressed(KeyCode::Char('q')) {
+            self.quit = true;
+            // dropping the engine puts the terminal back to normal
+            self.engine = None;
+            return true;
+        }
+        if engine.is_key_pressed(KeyCode::Char(' ')) {
+            self.paused = !self.paused;
+        }
+        false
+    }
+}
+
+#[cfg(feature = "visualize")]
+impl Visualizer for ConsoleVisualizer {
+    fn is_enabled(&self) -> bool {
+        !self.quit
+    }
+
+    fn show(&mut self, frame: &Frame) -> Control {
+        use console_engine::{pixel, Color, ConsoleEngine, KeyCode};
+
+        if self.quit {
+            return Control::Quit;
+        }
+        let color = |[r, g, b]: Rgb| Color::Rgb { r, g, b };
+        let (width, height) = (frame.width as u32, frame.height as u32 + 1);
+
+        let engine = match &mut self.engine {
+            Some(engine) => {
+                if (engine.get_width(), engine.get_height()) != (width, height) {
+                    engine.resize(width, height);
+                }
+                engine
+            }
+            None => self.engine.insert(
+                ConsoleEngine::init(width, height, self.fps)
+                    .expect("console visualization couldn't start"),
+            ),
+        };
+
+        engine.wait_frame();
+        engine.clear_screen();
+        for y in 0..frame.height {
+            for x in 0..frame.width {
+                let styled = frame.get(x, y).unwrap();
+                engine.set_pxl(
+                    x as i32,
+                    y as i32,
+                    pixel::pxl_fbg(styled.ch, color(styled.fg), color(styled.bg)),
+                );
+            }
+        }
+        engine.print(0, frame.height as i32, &frame.status);
+        engine.draw();
+
+        if self.handle_keys() {
+            return Control::Quit;
+        }
+        while self.paused {
+            let engine = self.engine.as_mut().unwrap();
+            engine.wait_frame();
+            let step = engine.is_key_pressed(KeyCode::Char('n'));
+            if self.handle_keys() {
+                return Control::Quit;
+            }
+            if step {
+                break;
+            }
+        }
+        Control::Continue
+    }
+}
+
+#[cfg(test)]
+mod visualize_tests {
+    use super::*;
+
+    const RED: Rgb = [255, 0, 0];
+    const YELLOW: Rgb = [255, 255, 0];
+
+    #[test]
+    fn frame_from_grid_test() {
+        let grid = Grid::new(vec![vec![true, false], vec![false, false]]);
+        struct Walls;
+        impl Palette<bool> for Walls {
+            fn color(&self, wall: bool) -> Rgb {
+                if wall {
+                    WHITE
+                } else {
+                    BLACK
+                }
+            }
+            fn glyph(&self, wall: bool) -> Option<char> {
+                wall.then_some('#')
+            }
+        }
+
+        let mut frame = Frame::from_grid(&grid, &Walls);
+        assert_eq!(frame.get(0, 0), Some(Styled::on('#', WHITE)));
+        assert_eq!(frame.get(0, 0).unwrap().fg, BLACK);
+        assert_eq!(frame.get(1, 0).unwrap().fg, WHITE);
+
+        frame.mark([1, 1].into(), '@', RED);
+        frame.highlight([0, 0].into(), YELLOW);
+        frame.set_status("step 1");
+        assert_eq!(frame.get(0, 0), Some(Styled::on('#', YELLOW)));
+        assert_eq!(frame.to_string(), "# \n @\nstep 1\n");
+    }
+
+    #[test]
+    fn push_row_test() {
+        let mut frame = Frame::new(3, 1);
+        frame.push_row("ab", RED);
+        frame.push_row("wxyz", BLACK);
+        assert_eq!(frame.height, 3);
+        assert_eq!(frame.get(1, 1), Some(Styled::on('b', RED)));
+        assert_eq!(frame.to_string(), "   \nab \nwxy\n");
+    }
+
+    #[test]
+    fn sparkline_test() {
+        assert_eq!(sparkline(&[3, 10, 17, 10]), "▁▄█▄");
+        assert_eq!(sparkline(&[5, 5]), "▁▁");
+        assert_eq!(sparkline(&[]), "");
+    }
+
+    #[test]
+    fn capture_test() {
+        let mut capture = Capture::keep_last(2);
+        for i in 0..3 {
+            let mut frame = Frame::new(1, 1);
+            frame.set_status(i.to_string());
+            assert_eq!(capture.show(&frame), Control::Continue);
+        }
+        assert_eq!(capture.shown(), 3);
+        assert_eq!(capture.frames().len(), 2);
+        assert_eq!(capture.frame(0), None);
+        assert_eq!(capture.frame(1).unwrap().status, "1");
+        assert_eq!(capture.last().unwrap().status, "2");
+        assert_eq!(capture.frame(3), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "snapshot missing")]
+    fn missing_snapshot_test() {
+        check_snapshot(
+            Path::new("snapshots/no_such_snapshot.txt"),
+            &Frame::new(1, 1),
+            false,
+        );
+    }
+
+    #[test]
+    fn shared_recording_test() {
+        // a slot of its own, so solutions running in other tests can't add frames to it
+        static SLOT: RecorderSlot = Mutex::new(None);
+        assert!(SharedRecording::in_slot(&SLOT).is_none());
+
+        let path = std::env::temp_dir().join(format!("shared_{}.cast", std::process::id()));
+        *SLOT.lock().unwrap() =
+            Some(crate::record::recorder(&path, RecordOptions::default()).unwrap());
+        // two runs, as with both parts of a day, add to the same recording
+        for _ in 0..2 {
+            let mut viz = SharedRecording::in_slot(&SLOT).unwrap();
+            assert!(viz.is_enabled());
+            assert_eq!(viz.show(&Frame::new(2, 1)), Control::Continue);
+        }
+        SLOT.lock().unwrap().take();
+        assert!(SharedRecording::in_slot(&SLOT).is_none());
+
+        let cast = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(cast.lines().count(), 3);
+        assert!(cast.starts_with("{\"version\": 2"));
+    }
+
+    #[test]
+    fn tee_test() {
+        let mut both = (NoVisualizer, Some(NoVisualizer));
+        assert!(!both.is_enabled());
+        assert_eq!(both.show(&Frame::new(1, 1)), Control::Quit);
+        assert!(!None::<NoVisualizer>.is_enabled());
+    }
+
+    #[test]
+    fn no_visualizer_test() {
+        let mut viz = NoVisualizer;
+        assert!(!viz.is_enabled());
+        assert_eq!(viz.show(&Frame::new(1, 1)), Control::Continue);
+    }
+}